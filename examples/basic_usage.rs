@@ -1,4 +1,4 @@
-#[allow(dead_code)]
+#![allow(dead_code)]
 use bitte::bitte;
 
 // Example 1: Apply bitte to an entire trait
@@ -27,7 +27,7 @@ trait AsyncMixedTrait {
     async fn no_send(&self) -> String;
 
     #[bitte(?Sync)]
-    async fn no_sync(&self) -> String;
+    async fn no_sync(&mut self) -> String;
 
     #[bitte(?Send, ?Sync)]
     async fn no_bounds(&self) -> String;
@@ -0,0 +1,158 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{GenericParam, Ident, ItemTrait, Path, ReturnType, TraitItem, ext::IdentExt};
+
+use crate::variant::forwarded_arguments;
+use crate::{AsyncBounds, turbofish};
+
+/// The `async fn`s of a trait which will be desugared, with their outputs,
+/// read before the trait is desugared.
+pub(crate) struct AsyncMethods(Vec<(Ident, ReturnType)>);
+
+impl AsyncMethods {
+    pub(crate) fn of(item: &ItemTrait, config: &AsyncBounds) -> syn::Result<Self> {
+        let mut methods = Vec::new();
+        for trait_item in &item.items {
+            if let TraitItem::Fn(method) = trait_item
+                && method.sig.asyncness.is_some()
+                && !config.for_method(&mut method.attrs.clone())?.skip
+            {
+                methods.push((method.sig.ident.clone(), method.sig.output.clone()));
+            }
+        }
+        Ok(AsyncMethods(methods))
+    }
+}
+
+/// Expand a desugared trait `Foo` into an extension trait `FooBlocking`,
+/// implemented for every implementor of `Foo`, whose `*_blocking` methods
+/// block on the futures of `Foo`’s `async` methods.
+///
+/// Futures are run by `block_on`, or by a tiny executor which parks the
+/// calling thread until the future wakes it, needing no runtime.
+pub(crate) fn expand(
+    desugared: &ItemTrait,
+    methods: &AsyncMethods,
+    block_on: Option<&Path>,
+) -> TokenStream {
+    let ident = &desugared.ident;
+    let blocking_ident = format_ident!("{}Blocking", ident);
+    let blanket = format_ident!("BitteBlocking");
+    let (_, ty_generics, where_clause) = desugared.generics.split_for_impl();
+
+    let executor = block_on.is_none().then(|| {
+        quote! {
+            fn block_on<F: ::std::future::Future>(future: F) -> F::Output {
+                struct Unpark(::std::thread::Thread);
+
+                impl ::std::task::Wake for Unpark {
+                    fn wake(self: ::std::sync::Arc<Self>) {
+                        self.0.unpark();
+                    }
+                }
+
+                let waker = ::std::task::Waker::from(::std::sync::Arc::new(Unpark(
+                    ::std::thread::current(),
+                )));
+                let mut cx = ::std::task::Context::from_waker(&waker);
+                let mut future = ::std::pin::pin!(future);
+                loop {
+                    match future.as_mut().poll(&mut cx) {
+                        ::std::task::Poll::Ready(output) => return output,
+                        ::std::task::Poll::Pending => ::std::thread::park(),
+                    }
+                }
+            }
+        }
+    });
+    let block_on = match block_on {
+        Some(path) => quote! { #path },
+        None => quote! { block_on },
+    };
+
+    let mut trait_methods = Vec::new();
+    let mut impl_methods = Vec::new();
+    for trait_item in &desugared.items {
+        let TraitItem::Fn(method) = trait_item else {
+            continue;
+        };
+        let Some((_, output)) = methods
+            .0
+            .iter()
+            .find(|(ident, _)| *ident == method.sig.ident)
+        else {
+            continue;
+        };
+
+        // A name of its own, so calls aren’t ambiguous with both traits in scope
+        let mut sig = method.sig.clone();
+        sig.ident = format_ident!("{}_blocking", method.sig.ident.unraw());
+        sig.output = output.clone();
+        let args = forwarded_arguments(&mut sig);
+        let method_ident = &method.sig.ident;
+        let turbofish = turbofish(&sig.generics);
+
+        let cfgs: Vec<_> = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .collect();
+        let doc = format!("Block the current thread on [`{ident}::{method_ident}`].");
+
+        trait_methods.push(quote! {
+            #[doc = #doc]
+            #(#cfgs)*
+            #[allow(
+                clippy::multiple_bound_locations,
+                clippy::type_complexity,
+                clippy::type_repetition_in_bounds
+            )]
+            #sig;
+        });
+        impl_methods.push(quote! {
+            #(#cfgs)*
+            #[inline]
+            #[allow(
+                clippy::multiple_bound_locations,
+                clippy::type_complexity,
+                clippy::type_repetition_in_bounds
+            )]
+            #sig {
+                #block_on(<Self as #ident #ty_generics>::#method_ident #turbofish(#(#args),*))
+            }
+        });
+    }
+
+    let mut impl_generics = desugared.generics.clone();
+    impl_generics
+        .params
+        .push(GenericParam::Type(blanket.clone().into()));
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let predicates = where_clause.map(|clause| &clause.predicates);
+
+    let vis = &desugared.vis;
+    let generics = &desugared.generics;
+    let doc = format!(
+        "A blocking version of [`{ident}`], for callers outside of an async runtime.\n\n\
+         Every implementor of [`{ident}`] implements `{blocking_ident}`."
+    );
+
+    quote! {
+        #[doc = #doc]
+        #vis trait #blocking_ident #generics: #ident #ty_generics #where_clause {
+            #(#trait_methods)*
+        }
+
+        const _: () = {
+            #executor
+
+            impl #impl_generics #blocking_ident #ty_generics for #blanket
+            where
+                #blanket: #ident #ty_generics,
+                #predicates
+            {
+                #(#impl_methods)*
+            }
+        };
+    }
+}
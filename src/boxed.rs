@@ -0,0 +1,255 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{
+    FnArg, GenericArgument, GenericParam, Generics, Lifetime, PathArguments, ReturnType, Signature,
+    Token, Type, TypeParamBound, WherePredicate, parse_quote, punctuated::Punctuated,
+};
+
+use crate::lifetimes;
+
+/// Rewrite an `async fn` signature to return a boxed future, the same way
+/// async-trait does:
+///
+/// ```ignore
+/// fn get<'life0, 'life1, 'async_trait>(
+///     &'life0 self,
+///     key: &'life1 str,
+/// ) -> Pin<Box<dyn Future<Output = Option<String>> + Send + 'async_trait>>
+/// where
+///     'life0: 'async_trait,
+///     'life1: 'async_trait,
+///     Self: 'async_trait;
+/// ```
+///
+/// Every elided lifetime in the arguments is named, so the future can be
+/// bounded by all of them, and `impl Trait` arguments are bounded by the
+/// future's lifetime. `outer` are the generics of the enclosing trait or
+/// impl, whose type parameters the future may also capture.
+///
+/// Returns the future’s output type, with its elided lifetimes named too,
+/// so it can be written in a `where` clause.
+pub(crate) fn box_signature(
+    sig: &mut Signature,
+    lifetime: &Lifetime,
+    bounds: Vec<TypeParamBound>,
+    outer: Option<&Generics>,
+) -> Type {
+    sig.asyncness = None;
+    outlive(sig, lifetime, outer);
+
+    let mut output: Type = match &sig.output {
+        ReturnType::Default => parse_quote! { () },
+        ReturnType::Type(_, ty) => (**ty).clone(),
+    };
+    if let Some(elided) = lifetimes::output_lifetime(sig) {
+        lifetimes::name_elided(&mut output, &elided);
+    }
+    sig.output = parse_quote! {
+        -> std::pin::Pin<Box<
+            dyn std::future::Future<Output = #output> #(+ #bounds)* + #lifetime
+        >>
+    };
+    output
+}
+
+/// Name every elided lifetime in a signature’s arguments, and require
+/// everything the future may capture to outlive `lifetime`, which is added
+/// as its last generic parameter. `impl Trait` arguments are bounded by
+/// `lifetime` in place.
+fn outlive(sig: &mut Signature, lifetime: &Lifetime, outer: Option<&Generics>) {
+    let has_self = sig.receiver().is_some() || mentions_self(sig);
+
+    // Name every elided lifetime in the arguments
+    let mut lifetimes = Lifetimes::default();
+    lifetimes.visit_inputs(&mut sig.inputs, lifetime);
+    let Lifetimes {
+        named, explicit, ..
+    } = lifetimes;
+
+    // Named lifetimes go before any other generic parameters
+    let existing: Vec<GenericParam> = sig.generics.params.iter().cloned().collect();
+    sig.generics.params.clear();
+    for name in &named {
+        sig.generics.params.push(parse_quote! { #name });
+    }
+    for param in existing {
+        sig.generics.params.push(param);
+    }
+    sig.generics.params.push(parse_quote! { #lifetime });
+
+    // Everything the future may capture must outlive it
+    let mut outlives: Vec<WherePredicate> = Vec::new();
+    let params = sig.generics.params.iter();
+    let outer_params = outer
+        .into_iter()
+        .flat_map(|generics| generics.params.iter());
+    for param in outer_params.chain(params) {
+        match param {
+            GenericParam::Lifetime(param) if param.lifetime != *lifetime => {
+                let name = &param.lifetime;
+                outlives.push(parse_quote! { #name: #lifetime });
+            }
+            GenericParam::Type(param) => {
+                let name = &param.ident;
+                outlives.push(parse_quote! { #name: #lifetime });
+            }
+            _ => {}
+        }
+    }
+    // Lifetimes of the enclosing trait or impl
+    for name in explicit {
+        let declared = sig.generics.lifetimes().any(|param| param.lifetime == name);
+        let predicate = parse_quote! { #name: #lifetime };
+        if !declared && !outlives.contains(&predicate) {
+            outlives.push(predicate);
+        }
+    }
+    if has_self {
+        outlives.push(parse_quote! { Self: #lifetime });
+    }
+    sig.generics.make_where_clause().predicates.extend(outlives);
+}
+
+/// The lifetimes of a function’s arguments.
+#[derive(Default)]
+pub(crate) struct Lifetimes {
+    /// Lifetimes which were elided, and have been given a name
+    pub(crate) named: Vec<Lifetime>,
+    /// Lifetimes which were already named
+    pub(crate) explicit: Vec<Lifetime>,
+    /// The one name to give every elided lifetime, instead of a new one
+    unified: Option<Lifetime>,
+}
+
+impl Lifetimes {
+    /// Give every elided lifetime the same name.
+    pub(crate) fn unified(lifetime: Lifetime) -> Self {
+        Lifetimes {
+            unified: Some(lifetime),
+            ..Lifetimes::default()
+        }
+    }
+
+    fn next(&mut self) -> Lifetime {
+        if let Some(lifetime) = &self.unified {
+            return lifetime.clone();
+        }
+
+        let name = Lifetime::new(&format!("'life{}", self.named.len()), Span::call_site());
+        self.named.push(name.clone());
+        name
+    }
+
+    /// Name the elided lifetimes in a function’s arguments, and bound its
+    /// `impl Trait` arguments by `future`, unless it is `'_`.
+    pub(crate) fn visit_inputs(
+        &mut self,
+        inputs: &mut Punctuated<FnArg, Token![,]>,
+        future: &Lifetime,
+    ) {
+        for input in inputs {
+            match input {
+                FnArg::Receiver(receiver) => {
+                    if receiver.colon_token.is_some() {
+                        self.visit(&mut receiver.ty, future);
+                    } else if let Some((_, name)) = &mut receiver.reference {
+                        let name = match name {
+                            Some(name) => {
+                                self.name(name);
+                                name.clone()
+                            }
+                            None => name.insert(self.next()).clone(),
+                        };
+                        if let Type::Reference(reference) = &mut *receiver.ty {
+                            reference.lifetime = Some(name);
+                        }
+                    }
+                }
+                FnArg::Typed(arg) => self.visit(&mut arg.ty, future),
+            }
+        }
+    }
+
+    fn name(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.next();
+        } else if lifetime.ident != "static" && !self.explicit.contains(lifetime) {
+            self.explicit.push(lifetime.clone());
+        }
+    }
+
+    /// Name the elided lifetimes in `ty`, including `'_`, and bound its
+    /// `impl Trait`s by `future`.
+    fn visit(&mut self, ty: &mut Type, future: &Lifetime) {
+        match ty {
+            Type::Reference(reference) => {
+                match &mut reference.lifetime {
+                    Some(lifetime) => self.name(lifetime),
+                    None => reference.lifetime = Some(self.next()),
+                }
+                self.visit(&mut reference.elem, future);
+
+                // `&impl A + 'future` is ambiguous without parentheses
+                if let Type::ImplTrait(impl_trait) = &*reference.elem
+                    && impl_trait.bounds.len() > 1
+                {
+                    let elem = &reference.elem;
+                    reference.elem = parse_quote! { (#elem) };
+                }
+            }
+            Type::Array(array) => self.visit(&mut array.elem, future),
+            Type::Group(group) => self.visit(&mut group.elem, future),
+            Type::Paren(paren) => self.visit(&mut paren.elem, future),
+            Type::Slice(slice) => self.visit(&mut slice.elem, future),
+            Type::Tuple(tuple) => {
+                for elem in &mut tuple.elems {
+                    self.visit(elem, future);
+                }
+            }
+            Type::Path(path) => {
+                if let Some(qself) = &mut path.qself {
+                    self.visit(&mut qself.ty, future);
+                }
+                for segment in &mut path.path.segments {
+                    if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        for arg in &mut args.args {
+                            match arg {
+                                GenericArgument::Lifetime(lifetime) => self.name(lifetime),
+                                GenericArgument::Type(ty) => self.visit(ty, future),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+            }
+            Type::TraitObject(object) => {
+                for bound in &mut object.bounds {
+                    if let TypeParamBound::Lifetime(lifetime) = bound {
+                        self.name(lifetime);
+                    }
+                }
+            }
+            Type::ImplTrait(impl_trait) if future.ident != "_" => {
+                impl_trait.bounds.push(parse_quote! { #future });
+            }
+            // Function pointers have their own lifetime scope
+            _ => {}
+        }
+    }
+}
+
+/// Whether a signature refers to `Self` outside its receiver, so the future
+/// may capture it.
+pub(crate) fn mentions_self(sig: &Signature) -> bool {
+    fn contains_self(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "Self",
+            TokenTree::Group(group) => contains_self(group.stream()),
+            _ => false,
+        })
+    }
+
+    let inputs = &sig.inputs;
+    let output = &sig.output;
+    contains_self(quote! { #inputs #output })
+}
@@ -0,0 +1,129 @@
+use syn::{GenericArgument, Ident, PathArguments, Type, WherePredicate, parse_quote};
+
+use crate::receiver::{Pointer, SelfBounds};
+
+/// The bounds a function’s generic parameters need for its future, which
+/// captures every argument, to be [`Send`].
+///
+/// Arguments are classified the same way as receivers:
+///
+/// | Argument                   | Requires             |
+/// | -------------------------- | -------------------- |
+/// | `T`, `&mut T`, `Box<T>`    | `T: Send`            |
+/// | `&T`                       | `T: Sync`            |
+/// | `Arc<T>`                   | `T: Send + Sync`     |
+/// | `Rc<T>`                    | never `Send`         |
+///
+/// A shared `Mutex<T>` only hands out its contents to one thread at a time,
+/// so it needs `T: Send` even behind `&`, and a shared `RwLock<T>` needs
+/// `T: Send + Sync`.
+///
+/// Any other generic type, like `Vec<T>` or `Option<&T>`, is assumed to be
+/// [`Send`] when its type arguments are. `impl Trait` arguments can’t be
+/// named in a `where` clause, so their bounds are added in place.
+#[derive(Debug)]
+pub(crate) struct Captures {
+    /// The generic type parameters bounds may be inferred for, and what
+    /// they need so far
+    params: Vec<(Ident, SelfBounds)>,
+}
+
+impl Captures {
+    pub(crate) fn new(params: impl IntoIterator<Item = Ident>) -> Self {
+        Captures {
+            params: params
+                .into_iter()
+                .map(|param| (param, SelfBounds::default()))
+                .collect(),
+        }
+    }
+
+    /// Record what an argument of type `ty` needs to be [`Send`].
+    pub(crate) fn capture(&mut self, ty: &mut Type) {
+        self.visit(ty, SelfBounds::SEND);
+    }
+
+    /// The `where` predicates for every parameter which needs a bound.
+    pub(crate) fn predicates(&self) -> impl Iterator<Item = WherePredicate> + '_ {
+        self.params
+            .iter()
+            .filter(|(_, needs)| *needs != SelfBounds::default())
+            .map(|(param, needs)| {
+                let bounds = needs.to_bounds();
+                parse_quote! { #param: #(#bounds)+* }
+            })
+    }
+
+    fn visit(&mut self, ty: &mut Type, needs: SelfBounds) {
+        match ty {
+            Type::Reference(reference) => {
+                let inner = if reference.mutability.is_some() {
+                    needs
+                } else {
+                    // `&T` is `Send` or `Sync` only when `T` is `Sync`
+                    SelfBounds::SYNC
+                };
+                self.visit(&mut reference.elem, inner);
+
+                // `&impl A + B` is ambiguous, so added bounds need parentheses
+                if let Type::ImplTrait(impl_trait) = &*reference.elem
+                    && impl_trait.bounds.len() > 1
+                {
+                    let elem = &reference.elem;
+                    reference.elem = parse_quote! { (#elem) };
+                }
+            }
+            Type::Group(group) => self.visit(&mut group.elem, needs),
+            Type::Paren(paren) => self.visit(&mut paren.elem, needs),
+            Type::Array(array) => self.visit(&mut array.elem, needs),
+            Type::Slice(slice) => self.visit(&mut slice.elem, needs),
+            Type::Tuple(tuple) => {
+                for elem in &mut tuple.elems {
+                    self.visit(elem, needs);
+                }
+            }
+            Type::ImplTrait(impl_trait) => {
+                for bound in needs.to_bounds() {
+                    if !impl_trait.bounds.iter().any(|existing| *existing == bound) {
+                        impl_trait.bounds.push(bound);
+                    }
+                }
+            }
+            Type::Path(path) if path.qself.is_none() => {
+                if let Some(ident) = path.path.get_ident()
+                    && let Some((_, param)) = self.params.iter_mut().find(|(p, _)| p == ident)
+                {
+                    *param = param.union(needs);
+                    return;
+                }
+
+                let needs = match Pointer::from_path(&path.path) {
+                    Some((Pointer::Arc, _)) => SelfBounds::SEND.union(SelfBounds::SYNC),
+                    // Nothing can make an `Rc` argument `Send`
+                    Some((Pointer::Rc, _)) => return,
+                    _ => match path.path.segments.last() {
+                        Some(last) if last.ident == "Mutex" => SelfBounds::SEND,
+                        Some(last) if last.ident == "RwLock" && needs.sync => {
+                            SelfBounds::SEND.union(SelfBounds::SYNC)
+                        }
+                        Some(last) if last.ident == "RwLock" => SelfBounds::SEND,
+                        _ => needs,
+                    },
+                };
+
+                for segment in &mut path.path.segments {
+                    if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        for arg in &mut args.args {
+                            if let GenericArgument::Type(ty) = arg {
+                                self.visit(ty, needs);
+                            }
+                        }
+                    }
+                }
+            }
+            // Trait objects, function pointers, and associated types are
+            // left to the compiler
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, format_ident, quote};
+use syn::{
+    Attribute, Ident, ImplItem, ItemImpl, ItemTrait, LitBool, Path, PathArguments, Token,
+    TraitItem, TypeParamBound, Visibility, WherePredicate, braced, bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+};
+
+use crate::{AsyncBounds, Setting, Source, is_bitte_attribute};
+
+thread_local! {
+    /// How many companion macros this process has emitted, to keep their
+    /// names unique.
+    static EMITTED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Emit a hidden `macro_rules!`, imported under the trait’s name, which
+/// passes the trait’s `#[bitte]` impl blocks on to `__bitte_impl`, along with
+/// the configuration the trait was desugared with.
+///
+/// The macro is imported along with the trait, from any module or crate, so
+/// impls always desugar their methods the same way the trait did. Its own
+/// name is hashed, since a `#[macro_export]` puts it at the crate’s root.
+pub(crate) fn expand(item: &ItemTrait, config: &AsyncBounds) -> TokenStream {
+    let ident = &item.ident;
+
+    let mut hasher = DefaultHasher::new();
+    item.to_token_stream().to_string().hash(&mut hasher);
+    EMITTED.with(|emitted| {
+        emitted.get().hash(&mut hasher);
+        emitted.set(emitted.get() + 1);
+    });
+    let name = format_ident!("__bitte_{}_{:016x}", ident, hasher.finish());
+
+    // Nested `#[bitte(...)]` attributes, applied to the impl’s methods too
+    let methods = item.items.iter().filter_map(|item| {
+        let TraitItem::Fn(method) = item else {
+            return None;
+        };
+        let attrs: Vec<&Attribute> = method
+            .attrs
+            .iter()
+            .filter(|attr| is_bitte_attribute(attr))
+            .collect();
+        let method_ident = &method.sig.ident;
+        (!attrs.is_empty()).then(|| quote! { #method_ident { #(#attrs)* } })
+    });
+
+    let config = ConfigTokens(config);
+    let vis = &item.vis;
+    let export = matches!(vis, Visibility::Public(_)).then(|| quote! { #[macro_export] });
+
+    quote! {
+        #[doc(hidden)]
+        #export
+        macro_rules! #name {
+            ($($item:tt)*) => {
+                ::bitte::__bitte_impl! {
+                    { #config }
+                    { #(#methods)* }
+                    $($item)*
+                }
+            };
+        }
+
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #name as #ident;
+    }
+}
+
+/// Pass an impl block to the companion macro of its trait, with the
+/// arguments of its own `#[bitte(...)]` attribute.
+pub(crate) fn forward(item: &ItemImpl, args: &TokenStream) -> syn::Result<TokenStream> {
+    let Some((_, path, _)) = &item.trait_ else {
+        return Err(syn::Error::new_spanned(
+            &item.self_ty,
+            "`trait` can only be applied to an impl block of a trait",
+        ));
+    };
+
+    // The macro shares the trait’s path, without its generic arguments
+    let mut path: Path = path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+
+    Ok(quote! {
+        #path! { (#args) #item }
+    })
+}
+
+/// The input to `__bitte_impl`.
+pub(crate) struct Companion {
+    /// The trait’s configuration
+    pub(crate) config: AsyncBounds,
+    /// The nested `#[bitte(...)]` attributes of the trait’s methods
+    methods: Vec<(Ident, Vec<Attribute>)>,
+    /// The arguments of the impl block’s own attribute
+    pub(crate) args: TokenStream,
+    pub(crate) item: ItemImpl,
+}
+
+impl Companion {
+    /// Add the attributes of the trait’s methods to the impl’s, ahead of the
+    /// impl’s own, so they refine the configuration the same way.
+    pub(crate) fn apply_method_attributes(&mut self) {
+        for item in &mut self.item.items {
+            if let ImplItem::Fn(method) = item
+                && let Some((_, attrs)) = self
+                    .methods
+                    .iter()
+                    .find(|(ident, _)| *ident == method.sig.ident)
+            {
+                method.attrs.splice(0..0, attrs.iter().cloned());
+            }
+        }
+    }
+}
+
+impl Parse for Companion {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        braced!(content in input);
+        let config = parse_config(&content)?;
+
+        let content;
+        braced!(content in input);
+        let mut methods = Vec::new();
+        while !content.is_empty() {
+            let ident = content.parse()?;
+            let attrs;
+            braced!(attrs in content);
+            methods.push((ident, attrs.call(Attribute::parse_outer)?));
+        }
+
+        let content;
+        parenthesized!(content in input);
+        let args = content.parse()?;
+
+        Ok(Companion {
+            config,
+            methods,
+            args,
+            item: input.parse()?,
+        })
+    }
+}
+
+/// Serialize a configuration, including where each setting came from,
+/// which the arguments of `#[bitte(...)]` can’t express.
+struct ConfigTokens<'a>(&'a AsyncBounds);
+
+impl ToTokens for ConfigTokens<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let config = self.0;
+        let (send, sync) = (setting_tokens(config.send), setting_tokens(config.sync));
+        let flags = [
+            config.skip,
+            config.supertraits,
+            config.send_arguments,
+            config.boxed,
+            config.named,
+        ];
+        let (future, output, predicates) = (&config.future, &config.output, &config.predicates);
+
+        tokens.extend(quote! {
+            #send #sync #(#flags)*
+            [#(#future)+*] [#(#output)+*] [#(#predicates),*]
+        });
+    }
+}
+
+fn setting_tokens(setting: Setting) -> TokenStream {
+    let source = match setting.source {
+        Source::Explicit => "explicit",
+        Source::Default => "default",
+        Source::Inferred => "inferred",
+    };
+    let source = Ident::new(source, Span::call_site());
+    let enabled = setting.enabled;
+    quote! { #source #enabled }
+}
+
+fn parse_config(input: ParseStream) -> syn::Result<AsyncBounds> {
+    let send = parse_setting(input)?;
+    let sync = parse_setting(input)?;
+    let mut flags = [false; 5];
+    for flag in &mut flags {
+        *flag = input.parse::<LitBool>()?.value;
+    }
+    let [skip, supertraits, send_arguments, boxed, named] = flags;
+
+    let content;
+    bracketed!(content in input);
+    let future = Punctuated::<TypeParamBound, Token![+]>::parse_terminated(&content)?;
+    let content;
+    bracketed!(content in input);
+    let output = Punctuated::<TypeParamBound, Token![+]>::parse_terminated(&content)?;
+    let content;
+    bracketed!(content in input);
+    let predicates = Punctuated::<WherePredicate, Token![,]>::parse_terminated(&content)?;
+
+    Ok(AsyncBounds {
+        send,
+        sync,
+        skip,
+        supertraits,
+        future: future.into_iter().collect(),
+        output: output.into_iter().collect(),
+        predicates: predicates.into_iter().collect(),
+        send_arguments,
+        boxed,
+        named,
+        ..AsyncBounds::default()
+    })
+}
+
+fn parse_setting(input: ParseStream) -> syn::Result<Setting> {
+    let source: Ident = input.parse()?;
+    let source = match source.to_string().as_str() {
+        "explicit" => Source::Explicit,
+        "default" => Source::Default,
+        "inferred" => Source::Inferred,
+        _ => return Err(syn::Error::new_spanned(source, "unknown setting source")),
+    };
+    let enabled = input.parse::<LitBool>()?.value;
+    Ok(Setting { enabled, source })
+}
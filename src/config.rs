@@ -0,0 +1,101 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::Span;
+
+/// Defaults for the crate being compiled, read from the
+/// `[package.metadata.bitte]` table of its `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.bitte]
+/// threads = true  # Default to both `Send` and `Sync`
+/// send = true     # Or set each one on its own
+/// sync = false
+/// ```
+///
+/// Unlike the `threads` feature, which Cargo unifies across the whole
+/// dependency graph, this only applies to the crate whose manifest it’s in.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub(crate) struct CrateConfig {
+    pub(crate) send: Option<bool>,
+    pub(crate) sync: Option<bool>,
+}
+
+thread_local! {
+    /// The configuration last read, keyed by manifest path, since the same
+    /// process may expand macros for more than one crate.
+    static CACHE: RefCell<Option<(PathBuf, Result<CrateConfig, String>)>> =
+        const { RefCell::new(None) };
+}
+
+/// Read the configuration of the crate being compiled.
+pub(crate) fn crate_config() -> syn::Result<CrateConfig> {
+    let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR") else {
+        return Ok(CrateConfig::default());
+    };
+    let manifest = PathBuf::from(dir).join("Cargo.toml");
+
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let config = match &*cache {
+            Some((path, config)) if *path == manifest => config.clone(),
+            _ => {
+                let config = read(&manifest);
+                *cache = Some((manifest, config.clone()));
+                config
+            }
+        };
+
+        config.map_err(|message| syn::Error::new(Span::call_site(), message))
+    })
+}
+
+fn read(manifest: &Path) -> Result<CrateConfig, String> {
+    // Builds outside of Cargo may not have a manifest
+    let Ok(contents) = std::fs::read_to_string(manifest) else {
+        return Ok(CrateConfig::default());
+    };
+
+    let manifest: toml::Table = contents
+        .parse()
+        .map_err(|e| format!("failed to parse {}: {e}", manifest.display()))?;
+
+    let Some(table) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("bitte"))
+    else {
+        return Ok(CrateConfig::default());
+    };
+    let Some(table) = table.as_table() else {
+        return Err("`package.metadata.bitte` must be a table".to_string());
+    };
+
+    let mut config = CrateConfig::default();
+    let mut threads = None;
+
+    for (key, value) in table {
+        let slot = match key.as_str() {
+            "threads" => &mut threads,
+            "send" => &mut config.send,
+            "sync" => &mut config.sync,
+            _ => {
+                return Err(format!(
+                    "unknown key `{key}` in `package.metadata.bitte`; \
+                     expected `threads`, `send`, or `sync`"
+                ));
+            }
+        };
+
+        match value.as_bool() {
+            Some(enabled) => *slot = Some(enabled),
+            None => return Err(format!("`package.metadata.bitte.{key}` must be a boolean")),
+        }
+    }
+
+    // `send` and `sync` refine `threads`
+    config.send = config.send.or(threads);
+    config.sync = config.sync.or(threads);
+
+    Ok(config)
+}
@@ -0,0 +1,180 @@
+use std::cell::RefCell;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    FnArg, Ident, ImplItemFn, ReturnType, Signature, Type, TypeParamBound, ext::IdentExt,
+    parse_quote,
+};
+
+use crate::{AsyncBound, AsyncBounds, DesugarAsync, Setting, strip_skips, turbofish};
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<TokenStream>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Report a warning at `span`.
+///
+/// Stable Rust has no API for proc macros to emit warnings, so this
+/// generates a use of a `#[deprecated]` constant whose note is `message`.
+/// The warnings are emitted by the [`bitte`](crate::bitte) entry point once
+/// expansion is done, so they need an item (or block) position to land in.
+pub(crate) fn warn(span: Span, message: &str) {
+    let warning = quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const bitte_warning: () = ();
+            bitte_warning
+        };
+    };
+
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(warning));
+}
+
+/// Remove and return every warning reported so far.
+pub(crate) fn take_warnings() -> Vec<TokenStream> {
+    WARNINGS.with(|warnings| warnings.take())
+}
+
+/// A hidden trait method whose default body holds the warnings reported so
+/// far, for a bodyless trait method named `ident`, since a trait can’t hold
+/// an unnamed `const` item.
+///
+/// The method requires `Self: Sized`, so the trait stays `dyn`-compatible.
+pub(crate) fn warnings_item(ident: &Ident) -> Option<TokenStream> {
+    let warnings = take_warnings();
+    if warnings.is_empty() {
+        return None;
+    }
+
+    let warnings_ident = format_ident!("__bitte_warnings_{}", ident.unraw());
+    Some(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        fn #warnings_ident()
+        where
+            Self: Sized,
+        {
+            #(#warnings)*
+        }
+    })
+}
+
+/// Check that an impl method’s future is [`Send`] with a static assertion
+/// spanned on the method’s name, instead of the bound on its return type.
+///
+/// rustc reports a future that isn’t `Send` pointing into the generated
+/// signature; the assertion points at the method instead.
+pub(crate) fn assert_send(method: &mut ImplItemFn) {
+    // The trait still requires `Send`, so only the assertion can fail
+    if let ReturnType::Type(_, ty) = &mut method.sig.output
+        && let Type::ImplTrait(impl_trait) = &mut **ty
+    {
+        let bounds = std::mem::take(&mut impl_trait.bounds);
+        impl_trait.bounds = bounds
+            .into_iter()
+            .filter(|bound| !matches!(bound, TypeParamBound::Trait(t) if t.path.is_ident("Send")))
+            .collect();
+    }
+
+    let ident = &method.sig.ident;
+    let future = &method.block;
+    let bounds = quote_spanned! {ident.span()=> Send };
+    let assertion = assertion(ident, &quote! { #future }, &bounds);
+    let future = quote_spanned! {ident.span()=> future };
+    method.block = parse_quote! {
+        {
+            #assertion
+            #future
+        }
+    };
+}
+
+/// Generate a function which only compiles if the future of the `async fn`
+/// `sig` has the auto traits `config.assert` asks for, leaving the function
+/// itself as written. `path` calls the function, like `Self::get`.
+///
+/// When checking for [`Send`], the generated function has the bounds on
+/// `Self` and the arguments that `#[bitte(Send)]` would add.
+pub(crate) fn assert_future(
+    sig: &mut Signature,
+    path: &TokenStream,
+    config: &AsyncBounds,
+) -> syn::Result<TokenStream> {
+    let ident = sig.ident.clone();
+    if sig.asyncness.is_none() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "`assert` checks the future of an `async fn`",
+        ));
+    }
+
+    let mut checker = sig.clone();
+    checker.ident = format_ident!("__bitte_assert_{}", ident.unraw());
+    strip_skips(sig)?;
+
+    // Bind every argument to a name, to pass them along
+    let mut args = Vec::new();
+    for (i, input) in checker.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(_) => args.push(quote! { self }),
+            FnArg::Typed(arg) => {
+                let name = format_ident!("__bitte_arg{}", i);
+                *arg.pat = parse_quote! { #name };
+                args.push(quote! { #name });
+            }
+        }
+    }
+
+    if config.assert.contains(&AsyncBound::Send(true)) {
+        // Desugar as the crate’s and attribute’s configuration would, with
+        // a `Send` future
+        let mut bounds = config.clone();
+        bounds.send = Setting::explicit(true);
+        bounds.assert = Vec::new();
+        bounds.capture = None;
+        checker.desugar_async(&bounds)?;
+    } else {
+        checker.asyncness = None;
+        strip_skips(&mut checker)?;
+    }
+    checker.constness = None;
+    checker.output = ReturnType::Default;
+
+    let turbofish = turbofish(&sig.generics);
+    let mut call = quote! { #path #turbofish(#(#args),*) };
+    if sig.unsafety.is_some() {
+        call = quote! { unsafe { #call } };
+    }
+
+    let bounds = config.assert.iter().map(|bound| match bound {
+        AsyncBound::Send(_) => quote_spanned! {ident.span()=> Send },
+        AsyncBound::Sync(_) => quote_spanned! {ident.span()=> Sync },
+    });
+    let assertion = assertion(&ident, &call, &quote! { #(#bounds)+* });
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(
+            dead_code,
+            clippy::multiple_bound_locations,
+            clippy::too_many_arguments,
+            clippy::type_complexity,
+            clippy::type_repetition_in_bounds
+        )]
+        #checker {
+            #assertion
+        }
+    })
+}
+
+/// Statements which bind `future`, and fail to compile unless its type
+/// implements `bounds`, spanned on `ident`.
+fn assertion(ident: &Ident, future: &TokenStream, bounds: &TokenStream) -> TokenStream {
+    quote_spanned! {ident.span()=>
+        let future = #future;
+
+        const fn assert_future<F: #bounds>(_: &F) {}
+        assert_future(&future);
+    }
+}
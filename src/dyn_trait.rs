@@ -0,0 +1,305 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    FnArg, GenericParam, ItemTrait, Lifetime, Signature, TraitItem, TraitItemFn, TypeParamBound,
+    ext::IdentExt, parse_quote,
+};
+
+use crate::boxed::box_signature;
+use crate::variant::{forwarded_arguments, has_impl_trait_argument};
+use crate::{AsyncBounds, Receiver, SelfBounds, add_extra_predicates, combine_error, take_skip};
+
+/// Expand a trait into a `dyn`-compatible version, `DynFoo`, whose `*_dyn`
+/// methods return boxed futures.
+///
+/// `DynFoo` is implemented for every implementor of `Foo`, and `Foo` is
+/// implemented for `dyn DynFoo` (and `Box<dyn DynFoo>`, when every method
+/// borrows `self`), so trait objects can be used wherever `Foo` is expected.
+pub(crate) fn expand(
+    original: &ItemTrait,
+    desugared: &ItemTrait,
+    config: &AsyncBounds,
+) -> syn::Result<TokenStream> {
+    let ident = &original.ident;
+    let dyn_ident = format_ident!("Dyn{}", ident);
+    let (_, ty_generics, where_clause) = original.generics.split_for_impl();
+
+    let object_lifetime = Lifetime::new("'object", Span::call_site());
+    let object = quote! { dyn #dyn_ident #ty_generics + #object_lifetime };
+    let blanket = format_ident!("BitteDyn");
+
+    let mut result = Ok(());
+    let mut self_bounds = SelfBounds::default();
+    let mut methods = Vec::new();
+    let mut blanket_items = Vec::new();
+    let mut object_items = Vec::new();
+    let mut box_items = Some(Vec::new());
+
+    for (item, desugared_item) in original.items.iter().zip(&desugared.items) {
+        let (TraitItem::Fn(method), TraitItem::Fn(desugared_method)) = (item, desugared_item)
+        else {
+            combine_error(
+                &mut result,
+                syn::Error::new_spanned(item, "`dyn` traits can only have methods"),
+            );
+            continue;
+        };
+
+        let method = match dyn_method(method, original, config) {
+            Ok(method) => method,
+            Err(e) => {
+                combine_error(&mut result, e);
+                continue;
+            }
+        };
+        self_bounds = self_bounds.union(method.self_bounds);
+
+        // A name of its own, so calls aren’t ambiguous with both traits in
+        // scope, since every implementor of `Foo` implements `DynFoo` too
+        let mut sig = method.sig;
+        let args = forwarded_arguments(&mut sig);
+        let method_ident = sig.ident.clone();
+        let dyn_method_ident = format_ident!("{}_dyn", method_ident.unraw());
+        sig.ident = dyn_method_ident.clone();
+        let attrs = &method.attrs;
+        let must_use = method.is_async.then(|| quote! { #[must_use] });
+        methods.push(quote! {
+            #(#attrs)*
+            #must_use
+            #[allow(
+                clippy::multiple_bound_locations,
+                clippy::type_complexity,
+                clippy::type_repetition_in_bounds
+            )]
+            #sig;
+        });
+
+        let call = quote! { <#blanket as #ident #ty_generics>::#method_ident(#(#args),*) };
+        let body = if method.is_async {
+            quote! { Box::pin(#call) }
+        } else {
+            call
+        };
+        blanket_items.push(forwarding_method(&sig, body));
+
+        // `Foo`’s own signature, implemented by calling through the object
+        let mut sig = desugared_method.sig.clone();
+        forwarded_arguments(&mut sig);
+        let call = quote! { <Self as #dyn_ident #ty_generics>::#dyn_method_ident(#(#args),*) };
+        object_items.push(forwarding_method(&sig, await_in(method.is_async, call)));
+
+        // `Box<dyn DynFoo>` only derefs to the object through a borrow
+        let deref = match &sig.inputs.first() {
+            Some(FnArg::Receiver(receiver)) if receiver.colon_token.is_none() => {
+                match &receiver.reference {
+                    Some(_) if receiver.mutability.is_some() => Some(quote! { &mut **self }),
+                    Some(_) => Some(quote! { &**self }),
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+        match (&mut box_items, deref) {
+            (Some(items), Some(deref)) => {
+                let args = args.iter().skip(1);
+                let call = quote! {
+                    <#object as #dyn_ident #ty_generics>::#dyn_method_ident(#deref, #(#args),*)
+                };
+                items.push(forwarding_method(&sig, await_in(method.is_async, call)));
+            }
+            _ => box_items = None,
+        }
+    }
+    result?;
+
+    let mut supertraits = original.supertraits.clone();
+    for bound in self_bounds.to_bounds() {
+        if !supertraits.iter().any(|existing| *existing == bound) {
+            supertraits.push(bound);
+        }
+    }
+    let colon = (!supertraits.is_empty()).then(|| quote! { : });
+
+    let vis = &original.vis;
+    let unsafety = &original.unsafety;
+    let generics = &original.generics;
+    let doc = format!(
+        "A [`dyn`-compatible](https://doc.rust-lang.org/reference/items/traits.html#dyn-compatibility) \
+         version of [`{ident}`], whose methods return boxed futures.\n\n\
+         Every implementor of [`{ident}`] implements `{dyn_ident}`, and \
+         `dyn {dyn_ident}` implements [`{ident}`]."
+    );
+
+    let mut blanket_generics = original.generics.clone();
+    blanket_generics.params.push(parse_quote! { #blanket });
+    let (blanket_generics, _, _) = blanket_generics.split_for_impl();
+    let predicates: Vec<syn::WherePredicate> = where_clause
+        .map(|clause| clause.predicates.iter().cloned().collect())
+        .unwrap_or_else(Vec::new);
+    let blanket_predicates = {
+        let self_bounds = self_bounds.to_bounds();
+        let mut predicates = predicates.clone();
+        predicates.push(parse_quote! {
+            #blanket: #ident #ty_generics #(+ #self_bounds)*
+        });
+        predicates
+    };
+
+    let mut object_generics = original.generics.clone();
+    object_generics
+        .params
+        .insert(0, parse_quote! { #object_lifetime });
+    let (object_generics, _, _) = object_generics.split_for_impl();
+
+    let box_impl = box_items.map(|items| {
+        quote! {
+            #unsafety impl #object_generics #ident #ty_generics for Box<#object>
+            where
+                #(#predicates,)*
+            {
+                #(#items)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis #unsafety trait #dyn_ident #generics #colon #supertraits #where_clause {
+            #(#methods)*
+        }
+
+        #unsafety impl #blanket_generics #dyn_ident #ty_generics for #blanket
+        where
+            #(#blanket_predicates,)*
+        {
+            #(#blanket_items)*
+        }
+
+        #unsafety impl #object_generics #ident #ty_generics for #object
+        where
+            #(#predicates,)*
+        {
+            #(#object_items)*
+        }
+
+        #box_impl
+    })
+}
+
+/// A method of the `dyn`-compatible trait.
+struct DynMethod {
+    attrs: Vec<syn::Attribute>,
+    sig: Signature,
+    is_async: bool,
+    /// The bounds the method’s future needs on `Self`
+    self_bounds: SelfBounds,
+}
+
+/// Build the `dyn`-compatible version of a method, boxing its future if it’s
+/// an `async fn`.
+fn dyn_method(
+    method: &TraitItemFn,
+    item: &ItemTrait,
+    config: &AsyncBounds,
+) -> syn::Result<DynMethod> {
+    let mut attrs = method.attrs.clone();
+    let config = config.for_method(&mut attrs)?;
+    let mut sig = method.sig.clone();
+
+    let is_async = sig.asyncness.is_some();
+    if is_async && config.skip {
+        return Err(syn::Error::new_spanned(
+            &sig,
+            "a `dyn` trait can't leave an `async fn` as written",
+        ));
+    }
+
+    if let Some(param) = sig
+        .generics
+        .params
+        .iter()
+        .find(|param| matches!(param, GenericParam::Type(_) | GenericParam::Const(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            param,
+            "methods of a `dyn` trait can't have generic parameters",
+        ));
+    }
+    if has_impl_trait_argument(&sig) {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "methods of a `dyn` trait can't take `impl Trait` arguments",
+        ));
+    }
+
+    match sig.inputs.first() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() || receiver.colon_token.is_some() => {}
+        Some(FnArg::Receiver(receiver)) => {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "methods of a `dyn` trait can't take `self` by value",
+            ));
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "methods of a `dyn` trait need a `self` receiver",
+            ));
+        }
+    }
+
+    let mut self_bounds = SelfBounds::default();
+    if is_async {
+        let resolved = config.resolve(Receiver::from_inputs(&sig.inputs).as_ref())?;
+        self_bounds = resolved.self_bounds;
+
+        for input in &mut sig.inputs {
+            if let FnArg::Typed(arg) = input {
+                take_skip(&mut arg.attrs)?;
+            }
+        }
+
+        let mut bounds: Vec<TypeParamBound> = Vec::new();
+        if resolved.send.enabled {
+            bounds.push(parse_quote! { Send });
+        }
+        bounds.extend(config.future.iter().cloned());
+
+        let lifetime = Lifetime::new("'bitte", Span::call_site());
+        let output = box_signature(&mut sig, &lifetime, bounds, Some(&item.generics));
+
+        add_extra_predicates(&mut sig, &config, &quote! { #output });
+    }
+
+    Ok(DynMethod {
+        attrs,
+        sig,
+        is_async,
+        self_bounds,
+    })
+}
+
+fn forwarding_method(sig: &Signature, body: TokenStream) -> TokenStream {
+    quote! {
+        #[inline]
+        #[allow(
+            clippy::multiple_bound_locations,
+            clippy::type_complexity,
+            clippy::type_repetition_in_bounds
+        )]
+        #sig {
+            #body
+        }
+    }
+}
+
+/// Await a boxed future inside the `impl Future` of the original trait, so
+/// the future only captures the lifetimes in its signature.
+fn await_in(is_async: bool, call: TokenStream) -> TokenStream {
+    if is_async {
+        quote! { async move { #call.await } }
+    } else {
+        call
+    }
+}
@@ -0,0 +1,140 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    GenericArgument, GenericParam, Ident, ItemTrait, PathArguments, ReturnType, TraitItem, Type,
+};
+
+use crate::{AsyncBounds, Setting};
+
+/// The `async fn`s of a trait whose named futures a helper trait bounds by
+/// [`Send`], read before the trait is desugared: all of them, except those
+/// skipped or opted out with a nested `#[bitte(?Send)]`.
+pub(crate) struct SendMethods(Vec<Ident>);
+
+impl SendMethods {
+    pub(crate) fn of(item: &ItemTrait, config: &AsyncBounds) -> syn::Result<Self> {
+        // The helper’s futures are `Send`, whatever the trait’s are
+        let mut bounded = config.clone();
+        bounded.send = Setting::explicit(true);
+
+        let mut methods = Vec::new();
+        for trait_item in &item.items {
+            if let TraitItem::Fn(method) = trait_item
+                && method.sig.asyncness.is_some()
+            {
+                let config = bounded.for_method(&mut method.attrs.clone())?;
+                if !config.skip && config.send.enabled {
+                    methods.push(method.sig.ident.clone());
+                }
+            }
+        }
+        Ok(SendMethods(methods))
+    }
+}
+
+/// Expand a desugared trait `Foo`, whose futures are named, into a helper
+/// trait `helper` with `Foo` as a supertrait, bounding the futures of
+/// `methods` by [`Send`].
+///
+/// Every implementor of `Foo` whose futures are `Send` implements the helper,
+/// and since the bounds are on a supertrait, generic code requiring
+/// `T: FooSend` can spawn them.
+pub(crate) fn expand(
+    desugared: &ItemTrait,
+    methods: &SendMethods,
+    helper: &Ident,
+) -> syn::Result<TokenStream> {
+    let ident = &desugared.ident;
+    let trait_args: Vec<TokenStream> = desugared
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                quote! { #lifetime }
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        })
+        .collect();
+
+    let mut bounds = Vec::new();
+    for trait_item in &desugared.items {
+        let TraitItem::Fn(method) = trait_item else {
+            continue;
+        };
+        if !methods.0.contains(&method.sig.ident) {
+            continue;
+        }
+
+        // Named, the method returns `Self::GetUserFuture<'future, ..>`
+        let ReturnType::Type(_, ty) = &method.sig.output else {
+            continue;
+        };
+        let Type::Path(path) = &**ty else {
+            continue;
+        };
+        let Some(segment) = path.path.segments.last() else {
+            continue;
+        };
+
+        // Lifetimes are bound for all of their values, but a bound for every
+        // type can’t be written
+        let mut lifetimes = Vec::new();
+        if let PathArguments::AngleBracketed(args) = &segment.arguments {
+            for arg in &args.args {
+                match arg {
+                    GenericArgument::Lifetime(lifetime) => lifetimes.push(lifetime),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            &method.sig.ident,
+                            "`helper` can't bound the future of a generic method, \
+                             opt it out with `#[bitte(?Send)]`",
+                        ));
+                    }
+                }
+            }
+        }
+
+        let future = &segment.ident;
+        bounds.push(quote! {
+            for<#(#lifetimes),*> #ident<#(#trait_args,)* #future<#(#lifetimes),*>: Send>
+        });
+    }
+
+    let blanket = format_ident!("BitteHelper");
+    let (_, ty_generics, where_clause) = desugared.generics.split_for_impl();
+    let mut impl_generics = desugared.generics.clone();
+    impl_generics
+        .params
+        .push(GenericParam::Type(blanket.clone().into()));
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let predicates = where_clause.map(|clause| &clause.predicates);
+
+    let vis = &desugared.vis;
+    let generics = &desugared.generics;
+    let doc = format!(
+        "[`{ident}`], with [`Send`] futures, for callers which spawn them.\n\n\
+         Every implementor of [`{ident}`] whose futures are `Send` implements \
+         `{helper}`."
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis trait #helper #generics: #ident #ty_generics #(+ #bounds)* #where_clause {}
+
+        impl #impl_generics #helper #ty_generics for #blanket
+        where
+            #blanket: #ident #ty_generics #(+ #bounds)*,
+            #predicates
+        {
+        }
+    })
+}
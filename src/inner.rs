@@ -0,0 +1,145 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Attribute, FnArg, Ident, ImplItemFn, ItemImpl, Visibility, ext::IdentExt, parse_quote};
+
+use crate::variant::forwarded_arguments;
+use crate::{strip_skips, turbofish};
+
+/// Move the body of an `async fn` in an impl block into a hidden inner
+/// `async fn`, which keeps the method’s attributes, and leave the method
+/// forwarding its arguments to it.
+///
+/// Attribute macros like `#[tracing::instrument]` then see a native
+/// `async fn`, and errors in the body point at the code as written. Doc
+/// comments stay on the method, and `#[cfg]` applies to both.
+///
+/// In an impl of `trait_ident`, the inner function’s name includes the
+/// trait’s, so it can’t be mistaken for one of the type’s own methods.
+pub(crate) fn split(
+    method: &mut ImplItemFn,
+    trait_ident: Option<&Ident>,
+) -> syn::Result<ImplItemFn> {
+    let mut inner = method.clone();
+    let ident = method.sig.ident.unraw();
+    inner.sig.ident = match trait_ident {
+        Some(trait_ident) => format_ident!("__bitte_{}_{}", snake_case(trait_ident), ident),
+        None => format_ident!("__bitte_{}", ident),
+    };
+    inner.vis = Visibility::Inherited;
+    inner.defaultness = None;
+    strip_skips(&mut inner.sig)?;
+
+    let (outer, attrs): (Vec<Attribute>, Vec<Attribute>) = method
+        .attrs
+        .drain(..)
+        .partition(|attr| attr.path().is_ident("doc") || attr.path().is_ident("cfg"));
+    inner.attrs = attrs
+        .into_iter()
+        .chain(
+            outer
+                .iter()
+                .filter(|attr| attr.path().is_ident("cfg"))
+                .cloned(),
+        )
+        .collect();
+    inner.attrs.insert(0, parse_quote! { #[doc(hidden)] });
+    method.attrs = outer;
+
+    // Name every argument, to pass them along
+    let mut args = Vec::new();
+    for (i, input) in method.sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                let self_token = &receiver.self_token;
+                args.push(quote! { #self_token });
+            }
+            FnArg::Typed(arg) => {
+                let ident = Ident::new(&format!("__arg{i}"), Span::mixed_site());
+                *arg.pat = parse_quote! { #ident };
+                args.push(quote! { #ident });
+            }
+        }
+    }
+
+    let turbofish = turbofish(&method.sig.generics);
+
+    let ident = &inner.sig.ident;
+    method.block = parse_quote! {
+        {
+            Self::#ident #turbofish(#(#args),*)
+        }
+    };
+
+    Ok(inner)
+}
+
+/// Convert a trait’s `UpperCamelCase` name to `snake_case`, for use in a
+/// function name.
+fn snake_case(ident: &Ident) -> String {
+    let name = ident.unraw().to_string();
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            // Split before a new word, keeping acronyms like `HTTP` together
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            if prev.is_some_and(|prev| prev != '_')
+                && (prev.is_some_and(|prev| !prev.is_uppercase())
+                    || next.is_some_and(|next| next.is_lowercase()))
+            {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+
+    snake
+}
+
+/// A trait impl, with the inner functions of its methods on a private
+/// extension trait, since the trait impl can’t hold them and the type may
+/// not be local. The extension trait has the trait as a supertrait, so
+/// the inner functions can name its associated types.
+///
+/// Both go in an anonymous `const` block, so the extension trait’s name is
+/// only in scope of the impl.
+pub(crate) fn trait_impl(item: &ItemImpl, functions: Vec<ImplItemFn>) -> TokenStream {
+    let Some((_, trait_path, _)) = &item.trait_ else {
+        return quote! { #item };
+    };
+    let ident = format_ident!("BitteInner");
+    let generics = &item.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let self_ty = &item.self_ty;
+
+    let declarations = functions.iter().map(|function| {
+        let mut sig = function.sig.clone();
+        forwarded_arguments(&mut sig);
+        let cfgs = function
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"));
+        quote! {
+            #(#cfgs)*
+            #sig;
+        }
+    });
+
+    quote! {
+        const _: () = {
+            trait #ident #generics: #trait_path #where_clause {
+                #(#declarations)*
+            }
+
+            impl #impl_generics #ident #ty_generics for #self_ty #where_clause {
+                #(#functions)*
+            }
+
+            #item
+        };
+    }
+}
@@ -2,13 +2,34 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    Attribute, FnArg, GenericArgument, Ident, ImplItem, ImplItemFn, ItemFn, ItemImpl, ItemTrait,
-    Meta, PathArguments, ReturnType, Signature, Token, TraitItem, TraitItemFn, Type, TypeImplTrait,
-    TypeParamBound, WherePredicate,
+    Attribute, Block, CapturedParam, FnArg, GenericParam, Generics, Ident, ImplItem, ImplItemFn,
+    ItemFn, ItemImpl, ItemTrait, Lifetime, Meta, Pat, Path, ReturnType, Signature, Token,
+    TraitItem, TraitItemFn, Type, TypeImplTrait, TypeParamBound, Visibility, WherePredicate,
     parse::{Parse, ParseStream},
     parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
 };
 
+mod blocking;
+mod boxed;
+mod capture;
+mod companion;
+mod config;
+mod diagnostic;
+mod dyn_trait;
+mod helper;
+mod inner;
+mod lifetimes;
+mod maybe_async;
+mod named;
+mod owned;
+mod receiver;
+mod variant;
+
+use capture::Captures;
+use receiver::{Receiver, SelfBounds};
+
 /// Whether to bound an `async fn` or its receiver by [`Send`] or [`Sync`].
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 enum AsyncBound {
@@ -16,6 +37,14 @@ enum AsyncBound {
     Sync(bool),
 }
 
+impl AsyncBound {
+    fn enabled(self) -> bool {
+        match self {
+            AsyncBound::Send(enabled) | AsyncBound::Sync(enabled) => enabled,
+        }
+    }
+}
+
 impl Parse for AsyncBound {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let enabled = if input.peek(Token![?]) {
@@ -34,42 +63,589 @@ impl Parse for AsyncBound {
     }
 }
 
+/// A single argument to the `#[bitte(...)]` attribute.
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum AsyncArg {
+    Bound(AsyncBound),
+    /// Leave the `async fn` as written.
+    Skip,
+    /// Bound a trait’s supertraits, instead of `Self` in each method.
+    Supertraits,
+    /// Extra bounds on the returned [`Future`], written `+ 'static + Unpin`.
+    Future(Vec<TypeParamBound>),
+    /// Bounds on the [`Future`]’s output, written `Output: Send`, which
+    /// become a `where` predicate on the return type.
+    Output(Vec<TypeParamBound>),
+    /// Extra `where` predicates, which must be the last argument.
+    Where(Vec<WherePredicate>),
+    /// Generate a bounded variant of a trait, written
+    /// `variant(SendStore: Send + Sync)`.
+    Variant(Variant),
+    /// Generate a `dyn`-compatible version of a trait.
+    Dyn,
+    /// Generate a helper trait bounding each named future by [`Send`],
+    /// written `helper(StoreSend)`.
+    Helper(Ident),
+    /// Return a boxed future, like async-trait, which is [`Send`] unless
+    /// written `boxed_local`.
+    Boxed {
+        send: bool,
+    },
+    /// Name each method’s future with an associated type.
+    Named,
+    /// Whether to take an impl block’s configuration from its trait, written
+    /// `trait`, or `?trait` for a trait declared without `#[bitte]`.
+    Trait(bool),
+    /// Leave an `async fn` as written, but check that its future is
+    /// [`Send`] or [`Sync`], written `assert(Send)`.
+    Assert(Vec<AsyncBound>),
+    /// Move each impl method’s body into a hidden inner `async fn`, which
+    /// keeps the method’s attributes.
+    Inner,
+    /// Capture exactly these generic parameters in the returned future,
+    /// written `capture('a, T)`.
+    Capture(Vec<CapturedParam>),
+    /// Generate a `*_owned` version of each `&self` method of a trait,
+    /// taking `self: Arc<Self>`.
+    Owned,
+    /// Generate a `spawn_*` method for each owned method, which spawns it
+    /// on Tokio.
+    Spawn,
+    /// Generate an extension trait blocking on each method’s future, with
+    /// the given `block_on` function, written `blocking(pollster::block_on)`.
+    Blocking(Option<Path>),
+    /// Compile the item as synchronous code when a cfg predicate holds,
+    /// written `maybe_async(feature = "blocking")`.
+    MaybeAsync(Meta),
+}
+
+impl Parse for AsyncArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![+]) {
+            let mut bounds = Vec::new();
+            while input.peek(Token![+]) {
+                input.parse::<Token![+]>()?;
+                bounds.push(input.parse()?);
+            }
+            return Ok(AsyncArg::Future(bounds));
+        }
+
+        if input.peek(Token![dyn]) {
+            input.parse::<Token![dyn]>()?;
+            return Ok(AsyncArg::Dyn);
+        }
+
+        if input.peek(Token![trait]) {
+            input.parse::<Token![trait]>()?;
+            return Ok(AsyncArg::Trait(true));
+        }
+
+        if input.peek(Token![?]) && input.peek2(Token![trait]) {
+            input.parse::<Token![?]>()?;
+            input.parse::<Token![trait]>()?;
+            return Ok(AsyncArg::Trait(false));
+        }
+
+        if input.peek(Token![where]) {
+            input.parse::<Token![where]>()?;
+            // Commas separate the predicates, so they take up the rest of
+            // the arguments
+            let mut predicates = vec![input.parse()?];
+            while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+                predicates.push(input.parse()?);
+            }
+            return Ok(AsyncArg::Where(predicates));
+        }
+
+        if input.peek(Ident) && input.peek2(Token![:]) {
+            let ident: Ident = input.parse()?;
+            if ident != "Output" {
+                return Err(syn::Error::new_spanned(ident, "Expected Output"));
+            }
+            input.parse::<Token![:]>()?;
+
+            let mut bounds = vec![input.parse()?];
+            while input.peek(Token![+]) {
+                input.parse::<Token![+]>()?;
+                bounds.push(input.parse()?);
+            }
+            return Ok(AsyncArg::Output(bounds));
+        }
+
+        if input.peek(Ident) && input.peek2(syn::token::Paren) {
+            let ident: Ident = input.parse()?;
+            let content;
+            syn::parenthesized!(content in input);
+
+            return match ident.to_string().as_str() {
+                "variant" => content.parse().map(AsyncArg::Variant),
+                "helper" => content.parse().map(AsyncArg::Helper),
+                "assert" => {
+                    let bounds =
+                        Punctuated::<AsyncBound, Token![+]>::parse_separated_nonempty(&content)?;
+                    if bounds.iter().any(|bound| !bound.enabled()) {
+                        return Err(syn::Error::new_spanned(
+                            ident,
+                            "`assert` checks for `Send` or `Sync`, not their absence",
+                        ));
+                    }
+                    Ok(AsyncArg::Assert(bounds.into_iter().collect()))
+                }
+                "blocking" => content.parse().map(|path| AsyncArg::Blocking(Some(path))),
+                "maybe_async" => content.parse().map(AsyncArg::MaybeAsync),
+                "capture" => {
+                    let params =
+                        Punctuated::<CapturedParam, Token![,]>::parse_terminated(&content)?;
+                    Ok(AsyncArg::Capture(params.into_iter().collect()))
+                }
+                _ => Err(syn::Error::new_spanned(
+                    ident,
+                    "Expected variant, helper, assert, blocking, maybe_async, or capture",
+                )),
+            };
+        }
+
+        if input.peek(Ident) {
+            let ident = input.fork().parse::<Ident>()?;
+            let arg = match ident.to_string().as_str() {
+                "skip" => Some(AsyncArg::Skip),
+                "supertraits" => Some(AsyncArg::Supertraits),
+                "boxed" => Some(AsyncArg::Boxed { send: true }),
+                "boxed_local" => Some(AsyncArg::Boxed { send: false }),
+                "named" => Some(AsyncArg::Named),
+                "inner" => Some(AsyncArg::Inner),
+                "owned" => Some(AsyncArg::Owned),
+                "spawn" => Some(AsyncArg::Spawn),
+                "blocking" => Some(AsyncArg::Blocking(None)),
+                // The same default as the maybe-async crate
+                "maybe_async" => Some(AsyncArg::MaybeAsync(parse_quote! { feature = "is_sync" })),
+                _ => None,
+            };
+            if let Some(arg) = arg {
+                input.parse::<Ident>()?;
+                if arg == AsyncArg::Spawn && !cfg!(feature = "tokio") {
+                    return Err(syn::Error::new_spanned(
+                        ident,
+                        "`spawn` needs bitte’s `tokio` feature",
+                    ));
+                }
+                return Ok(arg);
+            }
+        }
+
+        input.parse().map(AsyncArg::Bound)
+    }
+}
+
+/// A variant of a trait with its own [`Send`] and [`Sync`] bounds, which
+/// the original trait is implemented for.
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Variant {
+    ident: Ident,
+    bounds: Vec<AsyncBound>,
+}
+
+impl Parse for Variant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+
+        let bounds = Punctuated::<AsyncBound, Token![+]>::parse_separated_nonempty(input)?;
+        Ok(Variant {
+            ident,
+            bounds: bounds.into_iter().collect(),
+        })
+    }
+}
+
+/// Where a [`Send`] or [`Sync`] setting came from.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+enum Source {
+    /// Written in a `#[bitte(...)]` attribute
+    Explicit,
+    /// The crate-wide default, from the crate’s `Cargo.toml` or the
+    /// `threads` feature
+    Default,
+    /// Required by the method’s receiver type
+    Inferred,
+}
+
+/// Whether a [`Send`] or [`Sync`] bound is enabled, and why.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+struct Setting {
+    enabled: bool,
+    source: Source,
+}
+
+impl Setting {
+    fn explicit(enabled: bool) -> Self {
+        Setting {
+            enabled,
+            source: Source::Explicit,
+        }
+    }
+
+    /// Enable the bound if the receiver calls for it, unless it was
+    /// explicitly opted out of.
+    fn or_inferred(self, inferred: bool) -> Self {
+        if inferred && !self.enabled && self.source != Source::Explicit {
+            Setting {
+                enabled: true,
+                source: Source::Inferred,
+            }
+        } else {
+            self
+        }
+    }
+
+    fn is_opt_out(self) -> bool {
+        !self.enabled && self.source == Source::Explicit
+    }
+}
+
 /// Whether to bound an `async fn`’s [`Future`] by [`Send`] or
 /// its receiver by [`Sync`].
 #[derive(Debug, Clone)]
 struct AsyncBounds {
-    send: bool,
-    sync: bool,
+    send: Setting,
+    sync: Setting,
+    skip: bool,
+    /// Whether the bounds on `Self` belong in the trait’s supertraits
+    supertraits: bool,
+    /// Extra bounds on the returned [`Future`]
+    future: Vec<TypeParamBound>,
+    /// Bounds on the [`Future`]’s output
+    output: Vec<TypeParamBound>,
+    /// Extra `where` predicates for the desugared function
+    predicates: Vec<WherePredicate>,
+    /// A bounded variant to generate of a trait
+    variant: Option<Variant>,
+    /// Bound arguments as if the future were [`Send`], even when it isn’t,
+    /// so the trait can forward to a variant which is
+    send_arguments: bool,
+    /// A helper trait to generate, bounding each named future by [`Send`]
+    helper: Option<Ident>,
+    /// Whether to generate a `dyn`-compatible version of a trait
+    dyn_trait: bool,
+    /// Whether to return a boxed future with the same signature as
+    /// async-trait, instead of `impl Future`
+    boxed: bool,
+    /// Whether to return a future named by an associated type, instead of
+    /// `impl Future`
+    named: bool,
+    /// Whether to desugar an impl block the way its trait was, instead of
+    /// with this configuration
+    from_trait: Setting,
+    /// Auto traits to check an `async fn`’s future for, instead of
+    /// desugaring it
+    assert: Vec<AsyncBound>,
+    /// Whether to move an impl method’s body into an inner `async fn`
+    inner: bool,
+    /// The generic parameters to capture in the returned future, instead
+    /// of every one in scope
+    capture: Option<Vec<CapturedParam>>,
+    /// Whether to generate `'static` versions of a trait’s `&self` methods
+    owned: bool,
+    /// Whether to generate methods spawning the owned versions on Tokio
+    spawn: bool,
+    /// Whether to generate a blocking extension trait
+    blocking: bool,
+    /// The function the blocking trait runs futures with, instead of its
+    /// own executor
+    block_on: Option<Path>,
+    /// The cfg predicate under which to strip `async` and `.await`,
+    /// instead of desugaring
+    maybe_async: Option<Meta>,
 }
 
 impl Default for AsyncBounds {
     fn default() -> Self {
+        let threads = Setting {
+            enabled: cfg!(feature = "threads"),
+            source: Source::Default,
+        };
+
         AsyncBounds {
-            send: cfg!(feature = "threads"),
-            sync: cfg!(feature = "threads"),
+            send: threads,
+            sync: threads,
+            skip: false,
+            supertraits: false,
+            future: Vec::new(),
+            output: Vec::new(),
+            predicates: Vec::new(),
+            variant: None,
+            send_arguments: false,
+            helper: None,
+            dyn_trait: false,
+            boxed: false,
+            named: false,
+            from_trait: Setting {
+                enabled: true,
+                source: Source::Default,
+            },
+            assert: Vec::new(),
+            inner: false,
+            capture: None,
+            owned: false,
+            spawn: false,
+            blocking: false,
+            block_on: None,
+            maybe_async: None,
         }
     }
 }
 
 impl AsyncBounds {
-    fn from_attribute(attr: &Attribute) -> syn::Result<Self> {
+    /// The defaults for the crate being compiled, from its
+    /// `[package.metadata.bitte]`, falling back to the `threads` feature.
+    fn for_crate() -> syn::Result<Self> {
+        let crate_config = config::crate_config()?;
         let mut config = AsyncBounds::default();
 
+        if let Some(send) = crate_config.send {
+            config.send.enabled = send;
+        }
+        if let Some(sync) = crate_config.sync {
+            config.sync.enabled = sync;
+        }
+
+        Ok(config)
+    }
+
+    /// Apply the arguments of a `#[bitte(...)]` attribute on top of this
+    /// configuration, keeping any setting the attribute doesn’t mention.
+    fn with_attribute(&self, attr: &Attribute) -> syn::Result<Self> {
+        let mut config = self.clone();
+
         if let Meta::List(meta_list) = &attr.meta {
             let parsed = meta_list.parse_args_with(
-                syn::punctuated::Punctuated::<AsyncBound, syn::Token![,]>::parse_terminated,
+                syn::punctuated::Punctuated::<AsyncArg, syn::Token![,]>::parse_terminated,
             )?;
 
             for arg in parsed {
                 match arg {
-                    AsyncBound::Send(b) => config.send = b,
-                    AsyncBound::Sync(b) => config.sync = b,
+                    AsyncArg::Bound(AsyncBound::Send(b)) => config.send = Setting::explicit(b),
+                    AsyncArg::Bound(AsyncBound::Sync(b)) => config.sync = Setting::explicit(b),
+                    AsyncArg::Skip => config.skip = true,
+                    AsyncArg::Supertraits => config.supertraits = true,
+                    AsyncArg::Future(bounds) => config.future.extend(bounds),
+                    AsyncArg::Output(bounds) => config.output.extend(bounds),
+                    AsyncArg::Where(predicates) => config.predicates.extend(predicates),
+                    AsyncArg::Variant(variant) => config.variant = Some(variant),
+                    AsyncArg::Helper(ident) => config.helper = Some(ident),
+                    AsyncArg::Dyn => config.dyn_trait = true,
+                    AsyncArg::Named => config.named = true,
+                    AsyncArg::Trait(enabled) => config.from_trait = Setting::explicit(enabled),
+                    AsyncArg::Assert(bounds) => config.assert = bounds,
+                    AsyncArg::Inner => config.inner = true,
+                    AsyncArg::Capture(params) => config.capture = Some(params),
+                    AsyncArg::Owned => config.owned = true,
+                    AsyncArg::MaybeAsync(cfg) => config.maybe_async = Some(cfg),
+                    AsyncArg::Blocking(block_on) => {
+                        config.blocking = true;
+                        config.block_on = block_on;
+                    }
+                    AsyncArg::Spawn => {
+                        config.owned = true;
+                        config.spawn = true;
+                    }
+                    AsyncArg::Boxed { send } => {
+                        config.boxed = true;
+                        config.send = Setting::explicit(send);
+                    }
                 }
             }
         }
 
         Ok(config)
     }
+
+    /// Resolve the configuration for a method nested inside a `#[bitte]`
+    /// trait or impl block, removing any `#[bitte(...)]` attributes it has.
+    fn for_method(&self, attrs: &mut Vec<Attribute>) -> syn::Result<Self> {
+        let mut config = self.clone();
+        let mut result = Ok(());
+
+        attrs.retain(|attr| {
+            if !is_bitte_attribute(attr) {
+                return true;
+            }
+
+            match config.with_attribute(attr) {
+                Ok(merged) if merged.supertraits != self.supertraits => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`supertraits` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.variant != self.variant => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`variant` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.helper != self.helper => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`helper` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.named != self.named => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`named` applies to a whole trait or impl block"),
+                ),
+                Ok(merged) if merged.dyn_trait != self.dyn_trait => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`dyn` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.maybe_async != self.maybe_async => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(
+                        attr,
+                        "`maybe_async` applies to a whole trait or impl block",
+                    ),
+                ),
+                Ok(merged) if merged.blocking != self.blocking => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`blocking` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.spawn != self.spawn => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`spawn` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.owned != self.owned => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`owned` applies to a whole trait"),
+                ),
+                Ok(merged) if merged.from_trait != self.from_trait => combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(attr, "`trait` applies to a whole impl block"),
+                ),
+                Ok(merged) => config = merged,
+                Err(e) => combine_error(&mut result, e),
+            }
+            false
+        });
+
+        result.map(|()| config)
+    }
+
+    /// Resolve whether a method’s future is [`Send`] and its receiver
+    /// [`Sync`], and what that requires of `Self`.
+    fn resolve(&self, receiver: Option<&Receiver>) -> syn::Result<Resolved> {
+        let inferred = receiver.map(Receiver::inferred_bounds).unwrap_or_default();
+        let mut send = self.send.or_inferred(inferred.needs_send);
+        let sync = self.sync.or_inferred(inferred.needs_sync);
+
+        let Some(receiver) = receiver else {
+            return Ok(Resolved {
+                send,
+                self_bounds: SelfBounds::default(),
+                opted_out_sync: false,
+            });
+        };
+
+        if send.enabled && !receiver.can_send() {
+            if send.source == Source::Explicit {
+                return Err(syn::Error::new_spanned(
+                    &receiver.ty,
+                    "the future of a method taking `Rc<Self>` can never be `Send`; \
+                     use `Arc<Self>`, or opt out with `#[bitte(?Send)]`",
+                ));
+            }
+
+            // A crate-wide default can’t apply to this method
+            send.enabled = false;
+        }
+
+        // Require only what the receiver needs for those bounds to hold
+        let mut self_bounds = receiver.self_bounds(send.enabled, sync.enabled);
+
+        // An explicit `?Sync` wins, even over what a `Send` future needs
+        let opted_out_sync = self_bounds.sync && sync.is_opt_out();
+        if opted_out_sync {
+            self_bounds.sync = false;
+        }
+
+        Ok(Resolved {
+            send,
+            self_bounds,
+            opted_out_sync,
+        })
+    }
+}
+
+/// The bounds resolved for a single desugared method.
+struct Resolved {
+    send: Setting,
+    /// The bounds the method needs on `Self`
+    self_bounds: SelfBounds,
+    /// Whether `?Sync` removed a `Self: Sync` bound the future needs
+    opted_out_sync: bool,
+}
+
+/// Whether `attr` is `#[bitte]` or `#[bitte::bitte]`.
+fn is_bitte_attribute(attr: &Attribute) -> bool {
+    let segments = &attr.path().segments;
+    match segments.len() {
+        1 => segments[0].ident == "bitte",
+        2 => segments[0].ident == "bitte" && segments[1].ident == "bitte",
+        _ => false,
+    }
+}
+
+/// Remove the `#[bitte(skip)]` attributes from a signature’s parameters and
+/// generic parameters, for a function left as written.
+fn strip_skips(sig: &mut Signature) -> syn::Result<()> {
+    let mut result = Ok(());
+    let params = sig
+        .generics
+        .params
+        .iter_mut()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&mut param.attrs),
+            _ => None,
+        });
+    let args = sig.inputs.iter_mut().filter_map(|input| match input {
+        FnArg::Typed(arg) => Some(&mut arg.attrs),
+        FnArg::Receiver(_) => None,
+    });
+    for attrs in params.chain(args) {
+        if let Err(e) = take_skip(attrs) {
+            combine_error(&mut result, e);
+        }
+    }
+    result
+}
+
+/// Remove `#[bitte(skip)]` attributes from a function parameter, returning
+/// whether it had one.
+fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<bool> {
+    let mut skip = false;
+    let mut result = Ok(());
+
+    attrs.retain(|attr| {
+        if !is_bitte_attribute(attr) {
+            return true;
+        }
+
+        match attr.parse_args_with(Punctuated::<AsyncArg, Token![,]>::parse_terminated) {
+            Ok(args)
+                if !args.is_empty() && args.iter().all(|arg| matches!(arg, AsyncArg::Skip)) =>
+            {
+                skip = true;
+            }
+            Ok(_) => combine_error(
+                &mut result,
+                syn::Error::new_spanned(attr, "parameters only accept `#[bitte(skip)]`"),
+            ),
+            Err(e) => combine_error(&mut result, e),
+        }
+        false
+    });
+
+    result.map(|()| skip)
+}
+
+fn combine_error(result: &mut syn::Result<()>, error: syn::Error) {
+    match result {
+        Ok(()) => *result = Err(error),
+        Err(existing) => existing.combine(error),
+    }
 }
 
 /// Apply the bitte transformation to a trait, impl block, or async function
@@ -114,110 +690,624 @@ impl AsyncBounds {
 ///     async fn method(&self) -> u32;
 /// }
 /// ```
+///
+/// Apply to a free function, or a single method in an impl block:
+/// ```rust
+/// use bitte::bitte;
+///
+/// #[bitte(Send)]
+/// pub async fn fetch(id: u64) -> String {
+///     format!("item-{id}")
+/// }
+///
+/// struct Client;
+///
+/// impl Client {
+///     #[bitte(Send)]
+///     pub async fn get(&self, id: u64) -> String {
+///         fetch(id).await
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn bitte(args: TokenStream, input: TokenStream) -> TokenStream {
-    let config = if args.is_empty() {
-        AsyncBounds::default()
-    } else {
-        let args = proc_macro2::TokenStream::from(args);
-        let attr: Attribute = parse_quote! { #[bitte(#args)] };
-        match AsyncBounds::from_attribute(&attr) {
-            Ok(config) => config,
-            Err(e) => return e.to_compile_error().into(),
+    let args = proc_macro2::TokenStream::from(args);
+    let config = AsyncBounds::for_crate().and_then(|config| {
+        if args.is_empty() {
+            return Ok(config);
         }
+
+        let attr: Attribute = parse_quote! { #[bitte(#args)] };
+        config.with_attribute(&attr)
+    });
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => return e.to_compile_error().into(),
     };
 
-    // Try to parse as a trait first
-    if let Ok(mut trait_item) = syn::parse::<ItemTrait>(input.clone()) {
-        return trait_item.desugar_async(&config).into();
-    }
+    let expanded = match &config.maybe_async {
+        Some(cfg) => maybe_async::expand(&config, &args, input, cfg),
+        None => expand(&config, &args, input),
+    };
 
-    // Try to parse as an impl block
-    if let Ok(mut impl_item) = syn::parse::<ItemImpl>(input.clone()) {
-        return impl_item.desugar_async(&config).into();
-    }
+    let warnings = diagnostic::take_warnings();
+    expanded
+        .map(|tokens| quote! { #tokens #(#warnings)* })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    // Try to parse as a function
-    if let Ok(mut fn_item) = syn::parse::<ItemFn>(input.clone()) {
-        return fn_item.desugar_async(&config).into();
-    }
+/// Expand `#[bitte]` on whichever item it was applied to.
+fn expand(
+    config: &AsyncBounds,
+    args: &proc_macro2::TokenStream,
+    input: TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    // Boxed futures have the same signatures whatever the trait’s
+    // configuration, so they can implement async-trait traits too
+    let from_trait = if config.boxed {
+        config.from_trait == Setting::explicit(true)
+    } else {
+        config.from_trait.enabled
+    };
 
-    // If neither, try parsing as a trait item function
-    if let Ok(mut trait_fn) = syn::parse::<TraitItemFn>(input) {
-        return trait_fn.desugar_async(&config).into();
+    if from_trait
+        && let Ok(impl_item) = syn::parse::<ItemImpl>(input.clone())
+        && impl_item.trait_.is_some()
+    {
+        // Let the trait’s companion macro desugar the impl block
+        companion::forward(&impl_item, args)
+    } else if config.from_trait.enabled && config.from_trait.source == Source::Explicit {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`trait` can only be applied to an impl block of a trait",
+        ))
+    } else if let Ok(mut trait_item) = syn::parse::<ItemTrait>(input.clone()) {
+        // Try to parse as a trait first
+        trait_item.desugar_async(config)
+    } else if let Ok(mut impl_item) = syn::parse::<ItemImpl>(input.clone()) {
+        // Try to parse as an impl block
+        impl_item.desugar_async(config)
+    } else if config.supertraits {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`supertraits` can only be applied to a trait, or an impl block of one",
+        ))
+    } else if config.variant.is_some() {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`variant` can only be applied to a trait",
+        ))
+    } else if config.helper.is_some() {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`helper` can only be applied to a trait",
+        ))
+    } else if config.named {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`named` can only be applied to a trait, or an impl block of one",
+        ))
+    } else if config.dyn_trait {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`dyn` can only be applied to a trait",
+        ))
+    } else if config.owned {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`owned` can only be applied to a trait",
+        ))
+    } else if config.blocking {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "`blocking` can only be applied to a trait",
+        ))
+    } else if let Ok(mut impl_fn) = syn::parse::<ImplItemFn>(input.clone())
+        && (impl_fn.sig.receiver().is_some() || impl_fn.defaultness.is_some())
+    {
+        // Try to parse as a method inside an impl block, which would otherwise
+        // parse as a function too
+        if config.inner && impl_fn.vis == Visibility::Inherited {
+            return Err(syn::Error::new_spanned(
+                &impl_fn.sig.ident,
+                "`inner` can only be applied to a method with a visibility, or its impl block, \
+                 since a trait impl can't hold the inner function",
+            ));
+        }
+        let inner = (config.inner
+            && config.assert.is_empty()
+            && impl_fn.sig.asyncness.is_some()
+            && !config.skip)
+            .then(|| inner::split(&mut impl_fn, None))
+            .transpose();
+        let desugars = impl_fn.sig.asyncness.is_some() && !config.skip && config.assert.is_empty();
+        inner.and_then(|inner| {
+            impl_fn.desugar_async(config)?;
+            if desugars && let Some(params) = &config.capture {
+                lifetimes::capture(&mut impl_fn.sig, params);
+            }
+            let checker = if config.assert.is_empty() {
+                None
+            } else {
+                let ident = impl_fn.sig.ident.clone();
+                let path = quote! { Self::#ident };
+                Some(diagnostic::assert_future(&mut impl_fn.sig, &path, config)?)
+            };
+            emit_warnings_in(&mut impl_fn.block)?;
+            Ok(quote! { #impl_fn #checker #inner })
+        })
+    } else if let Ok(mut fn_item) = syn::parse::<ItemFn>(input.clone()) {
+        // Try to parse as a function
+        fn_item.desugar_async(config)
+    } else if let Ok(mut trait_fn) = syn::parse::<TraitItemFn>(input) {
+        // If neither, try parsing as a trait item function
+        trait_fn
+            .desugar_async(config)
+            .and_then(|_| match &mut trait_fn.default {
+                Some(block) => {
+                    emit_warnings_in(block)?;
+                    Ok(quote! { #trait_fn })
+                }
+                // Without a default body, the warnings need a body of their own
+                None => {
+                    let warnings = diagnostic::warnings_item(&trait_fn.sig.ident);
+                    Ok(quote! { #trait_fn #warnings })
+                }
+            })
+    } else {
+        Err(syn::Error::new(
+            Span::call_site(),
+            "bitte can only be applied to traits, impl blocks, functions, or trait methods",
+        ))
     }
+}
+
+/// Desugar an impl block with the configuration of its trait, passed along
+/// by the companion macro `#[bitte]` emits next to the trait.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __bitte_impl(input: TokenStream) -> TokenStream {
+    let expanded = syn::parse::<companion::Companion>(input).and_then(|mut companion| {
+        companion.apply_method_attributes();
+
+        let args = &companion.args;
+        let config = companion
+            .config
+            .with_attribute(&parse_quote! { #[bitte(#args)] })?;
+        companion.item.desugar_async(&config)
+    });
 
-    syn::Error::new(
-        Span::call_site(),
-        "bitte can only be applied to traits, impl blocks, functions, or trait methods",
-    )
-    .to_compile_error()
-    .into()
+    let warnings = diagnostic::take_warnings();
+    expanded
+        .map(|tokens| quote! { #tokens #(#warnings)* })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Emit the reported warnings at the top of a method body, since associated
+/// items can’t be placed next to the method.
+fn emit_warnings_in(block: &mut Block) -> syn::Result<()> {
+    for warning in diagnostic::take_warnings().into_iter().rev() {
+        block.stmts.insert(0, syn::parse2(warning)?);
+    }
+    Ok(())
 }
 
 trait DesugarAsync {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream;
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream>;
 }
 
 impl DesugarAsync for ItemTrait {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
-        for item in &mut self.items {
-            if let TraitItem::Fn(method) = item {
-                if method.sig.asyncness.is_some() {
-                    method.desugar_async(config);
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
+        if !config.assert.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`assert` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+        if config.capture.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`capture` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+        if config.inner {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`inner` can only be applied to an impl block, or a method in one",
+            ));
+        }
+        if config.named && (config.variant.is_some() || config.dyn_trait || config.boxed) {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`named` can't be combined with `variant`, `dyn`, or `boxed`",
+            ));
+        }
+        if config.helper.is_some() && !config.named {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`helper` bounds each method’s named future, so it needs `named`",
+            ));
+        }
+
+        if config.owned && config.variant.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`owned` can't be combined with `variant`",
+            ));
+        }
+        if config.blocking && config.variant.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`blocking` can't be combined with `variant`",
+            ));
+        }
+
+        if let Some(variant) = &config.variant {
+            if config.dyn_trait {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "`dyn` can't be combined with `variant`",
+                ));
+            }
+            return variant::expand(self, config, variant);
+        }
+
+        if config.dyn_trait && config.boxed {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`boxed` traits are already `dyn`-compatible, without `dyn`",
+            ));
+        }
+
+        // The `dyn` version is built from the methods as written
+        let original = config.dyn_trait.then(|| self.clone());
+        let companion = companion::expand(self, config);
+        let owned = if config.owned {
+            owned::expand(self, config)?
+        } else {
+            Vec::new()
+        };
+        let blocking_methods = config
+            .blocking
+            .then(|| blocking::AsyncMethods::of(self, config))
+            .transpose()?;
+        let send_methods = config
+            .helper
+            .as_ref()
+            .map(|_| helper::SendMethods::of(self, config))
+            .transpose()?;
+
+        let self_bounds = desugar_trait_methods(self, config)?;
+
+        // Bound the trait once, rather than `Self` in every method
+        if config.supertraits {
+            for bound in self_bounds.to_bounds() {
+                if !self.supertraits.iter().any(|existing| *existing == bound) {
+                    self.supertraits.push(bound);
                 }
             }
         }
-        quote! { #self }
+
+        let dyn_trait = original
+            .map(|original| dyn_trait::expand(&original, self, config))
+            .transpose()?;
+        let blocking = blocking_methods
+            .map(|methods| blocking::expand(self, &methods, config.block_on.as_ref()));
+        let helper = config
+            .helper
+            .as_ref()
+            .zip(send_methods)
+            .map(|(helper, methods)| helper::expand(self, &methods, helper))
+            .transpose()?;
+
+        // Declare each owned method just after the one it forwards to
+        for (ident, methods) in owned {
+            let i = self
+                .items
+                .iter()
+                .position(
+                    |item| matches!(item, TraitItem::Fn(existing) if existing.sig.ident == ident),
+                )
+                .map_or(self.items.len(), |i| i + 1);
+            self.items
+                .splice(i..i, methods.into_iter().map(TraitItem::Fn));
+        }
+
+        Ok(quote! { #self #companion #dyn_trait #blocking #helper })
+    }
+}
+
+/// Desugar the methods of a trait, returning the bounds they need on `Self`.
+fn desugar_trait_methods(item: &mut ItemTrait, config: &AsyncBounds) -> syn::Result<SelfBounds> {
+    let mut result = Ok(());
+    let mut self_bounds = SelfBounds::default();
+    let mut futures = Vec::new();
+
+    for (i, trait_item) in item.items.iter_mut().enumerate() {
+        if let TraitItem::Fn(method) = trait_item {
+            // Nested `#[bitte(...)]` attributes refine the trait-wide config
+            let desugared = config.for_method(&mut method.attrs).and_then(|config| {
+                let desugars = method.sig.asyncness.is_some() && !config.skip;
+                if desugars
+                    && let Ok(resolved) =
+                        config.resolve(Receiver::from_inputs(&method.sig.inputs).as_ref())
+                {
+                    self_bounds = self_bounds.union(resolved.self_bounds);
+                }
+
+                method.desugar_async(&config)?;
+
+                if desugars && config.named {
+                    if let Some(block) = &method.default {
+                        return Err(syn::Error::new_spanned(
+                            block,
+                            "a method with a named future can't have a default body",
+                        ));
+                    }
+                    let future = named::name_future(&mut method.sig, &item.generics)?;
+                    futures.push((i, future.to_trait_item()));
+                }
+                Ok(())
+            });
+
+            if let Err(e) = desugared {
+                combine_error(&mut result, e);
+            }
+        }
+    }
+
+    // Declare each future type just before its method
+    for (i, future) in futures.into_iter().rev() {
+        item.items.insert(i, future);
     }
+
+    result.map(|()| self_bounds)
 }
 
 impl DesugarAsync for ItemImpl {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
-        for item in &mut self.items {
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
+        if config.variant.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`variant` can only be applied to a trait",
+            ));
+        }
+        if config.helper.is_some() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`helper` can only be applied to a trait",
+            ));
+        }
+        if config.dyn_trait {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`dyn` can only be applied to a trait",
+            ));
+        }
+        if config.owned {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`owned` can only be applied to a trait",
+            ));
+        }
+        if config.blocking {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`blocking` can only be applied to a trait",
+            ));
+        }
+        if config.supertraits && self.trait_.is_none() {
+            return Err(syn::Error::new_spanned(
+                &self.self_ty,
+                "`supertraits` can only be applied to a trait, or an impl block of one",
+            ));
+        }
+
+        if config.named && self.trait_.is_none() {
+            return Err(syn::Error::new_spanned(
+                &self.self_ty,
+                "`named` can only be applied to a trait, or an impl block of one",
+            ));
+        }
+        if !config.assert.is_empty() && self.trait_.is_some() {
+            return Err(syn::Error::new_spanned(
+                &self.self_ty,
+                "`assert` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+        if config.capture.is_some() && self.trait_.is_some() {
+            return Err(syn::Error::new_spanned(
+                &self.self_ty,
+                "`capture` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+
+        let mut result = Ok(());
+        let mut futures = Vec::new();
+        let mut checkers = Vec::new();
+        let mut inners = Vec::new();
+        let trait_ident = self
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .map(|segment| segment.ident.clone());
+
+        for (i, item) in self.items.iter_mut().enumerate() {
             if let ImplItem::Fn(method) = item {
-                if method.sig.asyncness.is_some() {
-                    method.desugar_async(config);
+                // Nested `#[bitte(...)]` attributes refine the impl-wide config
+                let desugared = config.for_method(&mut method.attrs).and_then(|config| {
+                    // Leave the method as written, and check its future instead
+                    if !config.assert.is_empty() {
+                        if method.sig.asyncness.is_none() || config.skip {
+                            return Ok(());
+                        }
+                        if self.trait_.is_some() {
+                            return Err(syn::Error::new_spanned(
+                                &method.sig.ident,
+                                "`assert` can only be applied to a function, or an inherent impl block",
+                            ));
+                        }
+                        let ident = method.sig.ident.clone();
+                        let path = quote! { Self::#ident };
+                        let checker = diagnostic::assert_future(&mut method.sig, &path, &config)?;
+                        checkers.push(syn::parse2::<ImplItem>(checker)?);
+                        return Ok(());
+                    }
+
+                    let desugars = method.sig.asyncness.is_some() && !config.skip;
+                    if config.capture.is_some() && self.trait_.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            &method.sig.ident,
+                            "`capture` can only be applied to a function, or an inherent impl block",
+                        ));
+                    }
+                    let send = config
+                        .resolve(Receiver::from_inputs(&method.sig.inputs).as_ref())
+                        .map(|resolved| resolved.send);
+                    if desugars && config.inner {
+                        inners.push(inner::split(method, trait_ident.as_ref())?);
+                    }
+                    method.desugar_async(&config)?;
+
+                    if desugars && let Some(params) = &config.capture {
+                        lifetimes::capture(&mut method.sig, params);
+                    }
+
+                    // Point at the method if its body’s future isn’t `Send`
+                    if desugars
+                        && !config.boxed
+                        && !config.named
+                        && self.trait_.is_some()
+                        && let Ok(send) = send
+                        && send.enabled
+                    {
+                        diagnostic::assert_send(method);
+                    }
+
+                    if desugars && config.named {
+                        let future = named::name_future(&mut method.sig, &self.generics)?;
+                        futures.push((i, future));
+                    }
+                    Ok(())
+                });
+
+                if let Err(e) = desugared {
+                    combine_error(&mut result, e);
                 }
             }
         }
-        quote! { #self }
+
+        // Box each future, unless the impl already names its type, which an
+        // `async fn` can’t return
+        for (i, future) in futures.into_iter().rev() {
+            let defined = self
+                .items
+                .iter()
+                .any(|item| matches!(item, ImplItem::Type(assoc) if assoc.ident == future.ident));
+            if !defined {
+                self.items.insert(i, future.to_impl_item());
+            } else if let ImplItem::Fn(method) = &self.items[i] {
+                combine_error(
+                    &mut result,
+                    syn::Error::new_spanned(
+                        &method.sig.ident,
+                        format!(
+                            "this impl block defines `{}`, so `{}` must return it without `async`",
+                            future.ident, method.sig.ident,
+                        ),
+                    ),
+                );
+            }
+        }
+        self.items.extend(checkers);
+
+        // A trait impl can only define the trait’s items
+        if self.trait_.is_some() && !inners.is_empty() {
+            return result.map(|()| inner::trait_impl(self, inners));
+        }
+        self.items.extend(inners.into_iter().map(ImplItem::Fn));
+
+        result.map(|()| quote! { #self })
     }
 }
 
 impl DesugarAsync for ItemFn {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
-        if self.sig.asyncness.is_some() {
-            self.sig.desugar_async(config);
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
+        if config.inner {
+            return Err(syn::Error::new_spanned(
+                &self.sig.ident,
+                "`inner` can only be applied to an impl block, or a method in one",
+            ));
+        }
+        if !config.assert.is_empty() {
+            let ident = self.sig.ident.clone();
+            let checker = diagnostic::assert_future(&mut self.sig, &quote! { #ident }, config)?;
+            return Ok(quote! {
+                #self
+
+                const _: () = {
+                    #checker
+                };
+            });
+        }
+
+        if self.sig.asyncness.is_some() && !config.skip {
+            // Move the arguments into an async block around the body
+            let mut body = async_body(&mut self.sig.inputs, &self.block);
+            if config.boxed {
+                body = parse_quote! { { Box::pin(#body) } };
+            }
+
+            // Transform the signature
+            self.sig.desugar_async(config)?;
+            if let Some(params) = &config.capture {
+                lifetimes::capture(&mut self.sig, params);
+            }
+
+            *self.block = body;
+
             // Add #[must_use] attribute to async functions
             self.attrs.push(parse_quote! { #[must_use] });
             // Add lint suppression
             self.attrs.push(parse_quote! {
                 #[allow(
+                    clippy::async_yields_async,
+                    clippy::let_unit_value,
+                    clippy::multiple_bound_locations,
+                    clippy::no_effect_underscore_binding,
+                    clippy::redundant_locals,
+                    clippy::shadow_same,
                     clippy::type_complexity,
-                    clippy::type_repetition_in_bounds
+                    clippy::type_repetition_in_bounds,
+                    clippy::used_underscore_binding
                 )]
             });
         }
-        quote! { #self }
+        Ok(quote! { #self })
     }
 }
 
 impl DesugarAsync for ImplItemFn {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
-        if self.sig.asyncness.is_some() {
-            // Store the original body
-            let body = &self.block;
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
+        // `assert` leaves the method as written
+        if self.sig.asyncness.is_some() && !config.skip && config.assert.is_empty() {
+            // Move the arguments into an async block around the body, unless
+            // it already forwards them to an inner `async fn`
+            let mut body = if config.inner {
+                self.block.clone()
+            } else {
+                async_body(&mut self.sig.inputs, &self.block)
+            };
+            if config.boxed || config.named {
+                body = parse_quote! { { Box::pin(#body) } };
+            }
 
             // Transform the signature
-            self.sig.desugar_async(config);
+            self.sig.desugar_async(config)?;
 
-            // Wrap the body in an async block
-            self.block = parse_quote! {
-                {
-                    async move #body
-                }
-            };
+            self.block = body;
 
             // Add #[must_use] attribute to async methods
             self.attrs.push(parse_quote! { #[must_use] });
@@ -226,6 +1316,7 @@ impl DesugarAsync for ImplItemFn {
                 #[allow(
                     clippy::async_yields_async,
                     clippy::let_unit_value,
+                    clippy::multiple_bound_locations,
                     clippy::no_effect_underscore_binding,
                     clippy::shadow_same,
                     clippy::type_complexity,
@@ -234,14 +1325,56 @@ impl DesugarAsync for ImplItemFn {
                 )]
             });
         }
-        quote! { #self }
+        Ok(quote! { #self })
     }
 }
 
 impl DesugarAsync for TraitItemFn {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
-        if self.sig.asyncness.is_some() {
-            self.sig.desugar_async(config);
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
+        if !config.assert.is_empty() {
+            return Err(syn::Error::new_spanned(
+                &self.sig.ident,
+                "`assert` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+        if config.inner {
+            return Err(syn::Error::new_spanned(
+                &self.sig.ident,
+                "`inner` can only be applied to an impl block, or a method in one",
+            ));
+        }
+        if config.capture.is_some() {
+            return Err(syn::Error::new_spanned(
+                &self.sig.ident,
+                "`capture` can only be applied to a function, or an inherent impl block",
+            ));
+        }
+
+        if self.sig.asyncness.is_some() && !config.skip {
+            // A boxed future only bounds `Self` when a default body needs it,
+            // as async-trait does
+            let receiver = Receiver::from_inputs(&self.sig.inputs);
+            let boxed_self_bounds = match &receiver {
+                Some(receiver) if config.boxed && self.default.is_some() => {
+                    let resolved = config.resolve(Some(receiver))?;
+                    receiver.self_bounds(resolved.send.enabled, false)
+                }
+                _ => SelfBounds::default(),
+            };
+
+            // Move the arguments into an async block around a default body
+            let default = self
+                .default
+                .as_ref()
+                .map(|block| async_body(&mut self.sig.inputs, block));
+
+            self.sig.desugar_async(config)?;
+
+            let self_bounds = boxed_self_bounds.to_bounds();
+            if !self_bounds.is_empty() && !config.supertraits {
+                add_where_predicate(&mut self.sig, parse_quote! { Self: #(#self_bounds)+* });
+            }
+
             // Add #[must_use] attribute to async methods
             self.attrs.push(parse_quote! { #[must_use] });
             // Add lint suppression
@@ -251,6 +1384,7 @@ impl DesugarAsync for TraitItemFn {
                     #[allow(
                         clippy::async_yields_async,
                         clippy::let_unit_value,
+                        clippy::multiple_bound_locations,
                         clippy::no_effect_underscore_binding,
                         clippy::shadow_same,
                         clippy::type_complexity,
@@ -262,6 +1396,7 @@ impl DesugarAsync for TraitItemFn {
                 // Without default implementation
                 parse_quote! {
                     #[allow(
+                        clippy::multiple_bound_locations,
                         clippy::type_complexity,
                         clippy::type_repetition_in_bounds
                     )]
@@ -270,24 +1405,29 @@ impl DesugarAsync for TraitItemFn {
             self.attrs.push(lint_attr);
 
             // Transform default method body if present
-            if let Some(block) = &mut self.default {
-                let transformed = quote! {
-                    {
-                        async move #block
-                    }
-                };
-                self.default = Some(parse_quote! { #transformed });
+            if let Some(body) = default {
+                self.default = Some(if config.boxed {
+                    parse_quote! { { Box::pin(#body) } }
+                } else {
+                    body
+                });
             }
         }
-        quote! { #self }
+        Ok(quote! { #self })
     }
 }
 
 impl DesugarAsync for Signature {
-    fn desugar_async(&mut self, config: &AsyncBounds) -> proc_macro2::TokenStream {
+    fn desugar_async(&mut self, config: &AsyncBounds) -> syn::Result<proc_macro2::TokenStream> {
         // Remove the async keyword
         self.asyncness = None;
 
+        // `Output` bounds name the output type in a `where` clause, where
+        // its lifetimes can’t be elided
+        if !config.output.is_empty() && !config.boxed {
+            lifetimes::name_elided_output(self);
+        }
+
         // Extract the original return type
         let output_type = match &self.output {
             ReturnType::Default => quote! { () },
@@ -299,11 +1439,64 @@ impl DesugarAsync for Signature {
             vec![parse_quote! { std::future::Future<Output = #output_type> }];
 
         // Check receiver type to determine bounds
-        let receiver_bounds = analyze_receiver(&self.inputs);
+        let receiver = Receiver::from_inputs(&self.inputs);
 
-        if config.send || receiver_bounds.needs_send {
+        // Parameters marked `#[bitte(skip)]` are left out of inference
+        let mut result = Ok(());
+        let mut captures = Captures::new(self.generics.params.iter_mut().filter_map(|param| {
+            let GenericParam::Type(param) = param else {
+                return None;
+            };
+            match take_skip(&mut param.attrs) {
+                Ok(skip) => (!skip).then(|| param.ident.clone()),
+                Err(e) => {
+                    combine_error(&mut result, e);
+                    None
+                }
+            }
+        }));
+        let mut captured = Vec::new();
+        for input in &mut self.inputs {
+            if let FnArg::Typed(arg) = input {
+                match take_skip(&mut arg.attrs) {
+                    Ok(false) => captured.push(&mut *arg.ty),
+                    Ok(true) => {}
+                    Err(e) => combine_error(&mut result, e),
+                }
+            }
+        }
+        result?;
+
+        let resolved = config.resolve(receiver.as_ref())?;
+
+        if resolved.send.enabled {
             bounds.push(parse_quote! { Send });
         }
+        bounds.extend(config.future.iter().cloned());
+
+        if config.boxed {
+            if config.capture.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &self.ident,
+                    "`capture` can't be combined with `boxed`",
+                ));
+            }
+            // Leave out the inferred bounds, to match async-trait
+            let lifetime = Lifetime::new("'async_trait", Span::call_site());
+            let output = boxed::box_signature(self, &lifetime, bounds.split_off(1), None);
+            add_extra_predicates(self, config, &quote! { #output });
+            return Ok(quote! { #self });
+        }
+
+        if resolved.send.enabled || config.send_arguments {
+            // The future owns every argument, so they must be `Send` too
+            for ty in captured {
+                captures.capture(ty);
+            }
+            for predicate in captures.predicates() {
+                add_where_predicate(self, predicate);
+            }
+        }
 
         // Create the new return type
         let impl_trait = TypeImplTrait {
@@ -316,76 +1509,114 @@ impl DesugarAsync for Signature {
             Box::new(Type::ImplTrait(impl_trait)),
         );
 
-        // Add Self: Sync bound if needed
-        if config.sync || receiver_bounds.needs_sync {
-            add_self_sync_bound(self);
+        if let Some(receiver) = &receiver
+            && resolved.opted_out_sync
+        {
+            diagnostic::warn(
+                receiver.ty.span(),
+                "`?Sync` removes the `Self: Sync` bound this receiver needs for a `Send` \
+                 future; implementors must still be `Sync`",
+            );
         }
 
-        quote! { #self }
+        // In `supertraits` mode, the trait requires these bounds instead
+        let self_bounds = resolved.self_bounds.to_bounds();
+        if !self_bounds.is_empty() && !config.supertraits {
+            add_where_predicate(self, parse_quote! { Self: #(#self_bounds)+* });
+        }
+
+        add_extra_predicates(self, config, &output_type);
+
+        Ok(quote! { #self })
     }
 }
 
-struct ReceiverBounds {
-    needs_send: bool,
-    needs_sync: bool,
-}
-
-fn analyze_receiver(inputs: &syn::punctuated::Punctuated<FnArg, syn::Token![,]>) -> ReceiverBounds {
-    if let Some(FnArg::Receiver(receiver)) = inputs.first() {
-        match &*receiver.ty {
-            // Arc<Self> requires both Send and Sync
-            Type::Path(type_path) => {
-                if let Some(segment) = type_path.path.segments.last() {
-                    if segment.ident == "Arc" {
-                        if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                            if args.args.len() == 1 {
-                                if let GenericArgument::Type(Type::Path(inner)) = &args.args[0] {
-                                    if inner.path.is_ident("Self") {
-                                        return ReceiverBounds {
-                                            needs_send: true,
-                                            needs_sync: true,
-                                        };
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+/// Add the configured `Output` bounds and `where` predicates.
+fn add_extra_predicates(
+    sig: &mut Signature,
+    config: &AsyncBounds,
+    output_type: &proc_macro2::TokenStream,
+) {
+    if !config.output.is_empty() {
+        let output = &config.output;
+        add_where_predicate(sig, parse_quote! { #output_type: #(#output)+* });
+    }
+    for predicate in &config.predicates {
+        add_where_predicate(sig, predicate.clone());
+    }
+}
+
+/// Wrap a function body in an `async move` block, the way rustc lowers an
+/// `async fn`.
+///
+/// Every argument is moved into the block, so the future takes ownership of
+/// all of them (even if the body never uses one) and drops them when it
+/// completes, after the body’s own locals:
+///
+/// - An argument bound to a plain identifier is rebound by name, with its
+///   `mut` moved to the binding inside the future.
+/// - Any other pattern, including `_`, becomes an argument named `__argN`,
+///   which is rebound and then destructured by the original pattern.
+/// - A `self` receiver is moved in, even if the body doesn’t use it.
+fn async_body(inputs: &mut Punctuated<FnArg, Token![,]>, body: &Block) -> Block {
+    let mut rebinds = Vec::new();
+
+    for (i, input) in inputs.iter_mut().enumerate() {
+        let arg = match input {
+            FnArg::Receiver(receiver) => {
+                // `self` can’t be rebound, but mentioning it moves it in
+                let self_token = &receiver.self_token;
+                rebinds.push(quote! { let _ = &#self_token; });
+                continue;
             }
-            // &self requires only Sync
-            Type::Reference(type_ref) if type_ref.mutability.is_none() => {
-                return ReceiverBounds {
-                    needs_send: false,
-                    needs_sync: true,
-                };
+            FnArg::Typed(arg) => arg,
+        };
+
+        match &mut *arg.pat {
+            Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                let mutability = pat.mutability.take();
+                let ident = &pat.ident;
+                rebinds.push(quote! { let #mutability #ident = #ident; });
             }
-            // Other receiver types (self, &mut self) require Send
-            _ => {
-                return ReceiverBounds {
-                    needs_send: true,
-                    needs_sync: false,
-                };
+            pat => {
+                let ident = Ident::new(&format!("__arg{i}"), Span::mixed_site());
+                let pat = std::mem::replace(pat, parse_quote! { #ident });
+                rebinds.push(quote! {
+                    let #ident = #ident;
+                    let #pat = #ident;
+                });
             }
         }
     }
 
-    ReceiverBounds {
-        needs_send: false,
-        needs_sync: false,
+    // Like rustc, drop the body’s temporaries before the arguments
+    let ret = Ident::new("__ret", Span::mixed_site());
+    parse_quote! {
+        {
+            async move {
+                #(#rebinds)*
+                let #ret = #body;
+                #ret
+            }
+        }
     }
 }
 
-fn add_self_sync_bound(sig: &mut Signature) {
-    let sync_bound: WherePredicate = parse_quote! { Self: Sync };
-
-    if sig.generics.where_clause.is_none() {
-        sig.generics.where_clause = Some(parse_quote! { where });
-    }
+/// Turbofish the type and const parameters of a method, to call it.
+fn turbofish(generics: &Generics) -> Option<proc_macro2::TokenStream> {
+    // Late-bound lifetimes can’t be given explicitly
+    let params: Vec<&Ident> = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    (!params.is_empty()).then(|| quote! { ::<#(#params),*> })
+}
 
-    sig.generics
-        .where_clause
-        .as_mut()
-        .unwrap()
-        .predicates
-        .push(sync_bound);
+fn add_where_predicate(sig: &mut Signature, predicate: WherePredicate) {
+    sig.generics.make_where_clause().predicates.push(predicate);
 }
@@ -0,0 +1,109 @@
+use proc_macro2::Span;
+use syn::{
+    CapturedParam, FnArg, GenericArgument, Lifetime, PathArguments, ReturnType, Signature, Type,
+    parse_quote,
+};
+
+use crate::boxed::Lifetimes;
+
+/// Make a desugared signature’s `impl Future` capture exactly the parameters
+/// in `params`, with `use<..>`.
+///
+/// Without it, the future captures every lifetime in scope, even in crates
+/// on an edition before 2024, since the tokens bitte generates carry its
+/// own edition.
+pub(crate) fn capture(sig: &mut Signature, params: &[CapturedParam]) {
+    // A boxed future already outlives a lifetime of its own
+    if let ReturnType::Type(_, ty) = &mut sig.output
+        && let Type::ImplTrait(impl_trait) = &mut **ty
+    {
+        impl_trait.bounds.push(parse_quote! { use<#(#params),*> });
+    }
+}
+
+/// Name the elided lifetimes of an `async fn`’s arguments, and those of its
+/// output after them, so the output type can be written in a `where` clause.
+pub(crate) fn name_elided_output(sig: &mut Signature) {
+    let ReturnType::Type(_, output) = &sig.output else {
+        return;
+    };
+    let mut named = (**output).clone();
+    name_elided(&mut named, &Lifetime::new("'elided", Span::call_site()));
+    if named == **output {
+        return;
+    }
+
+    let mut lifetimes = Lifetimes::default();
+    lifetimes.visit_inputs(&mut sig.inputs, &Lifetime::new("'_", Span::call_site()));
+    for (i, name) in lifetimes.named.iter().enumerate() {
+        sig.generics.params.insert(i, parse_quote! { #name });
+    }
+
+    if let Some(lifetime) = output_lifetime(sig)
+        && let ReturnType::Type(_, output) = &mut sig.output
+    {
+        name_elided(output, &lifetime);
+    }
+}
+
+/// The lifetime elision gives a function’s output, once the arguments’
+/// lifetimes are named: that of `&self`, or else the only one there is.
+pub(crate) fn output_lifetime(sig: &Signature) -> Option<Lifetime> {
+    let receiver = sig.inputs.iter().find_map(|input| match input {
+        FnArg::Receiver(receiver) => match &*receiver.ty {
+            Type::Reference(reference) => reference.lifetime.clone(),
+            _ => None,
+        },
+        FnArg::Typed(_) => None,
+    });
+    receiver.or_else(|| {
+        let mut lifetimes = Lifetimes::default();
+        lifetimes.visit_inputs(
+            &mut sig.inputs.clone(),
+            &Lifetime::new("'_", Span::call_site()),
+        );
+        let mut all = lifetimes.named.into_iter().chain(lifetimes.explicit);
+        let lifetime = all.next()?;
+        all.next().is_none().then_some(lifetime)
+    })
+}
+
+/// Give the elided lifetimes in `ty`, including `'_`, the name `lifetime`.
+pub(crate) fn name_elided(ty: &mut Type, lifetime: &Lifetime) {
+    match ty {
+        Type::Reference(reference) => {
+            match &mut reference.lifetime {
+                Some(existing) if existing.ident == "_" => *existing = lifetime.clone(),
+                Some(_) => {}
+                None => reference.lifetime = Some(lifetime.clone()),
+            }
+            name_elided(&mut reference.elem, lifetime);
+        }
+        Type::Array(array) => name_elided(&mut array.elem, lifetime),
+        Type::Group(group) => name_elided(&mut group.elem, lifetime),
+        Type::Paren(paren) => name_elided(&mut paren.elem, lifetime),
+        Type::Slice(slice) => name_elided(&mut slice.elem, lifetime),
+        Type::Tuple(tuple) => {
+            for elem in &mut tuple.elems {
+                name_elided(elem, lifetime);
+            }
+        }
+        Type::Path(path) => {
+            for segment in &mut path.path.segments {
+                if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                    for arg in &mut args.args {
+                        match arg {
+                            GenericArgument::Lifetime(existing) if existing.ident == "_" => {
+                                *existing = lifetime.clone();
+                            }
+                            GenericArgument::Type(ty) => name_elided(ty, lifetime),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        // Trait objects and function pointers have their own defaults
+        _ => {}
+    }
+}
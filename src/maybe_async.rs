@@ -0,0 +1,238 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use quote::{ToTokens, quote};
+use syn::{
+    Attribute, Expr, ExprBlock, ImplItem, ImplItemFn, Item, ItemImpl, ItemTrait, Macro, Meta,
+    Signature, TraitItemFn,
+    parse::{Parse, ParseStream, Parser},
+    visit_mut::{self, VisitMut},
+};
+
+use crate::{AsyncBounds, combine_error, diagnostic, is_bitte_attribute, strip_skips};
+
+/// Expand a trait, impl block or method twice: as written, with `async` and
+/// every `.await` stripped, when `cfg` holds, and desugared as usual when it
+/// doesn’t.
+///
+/// Libraries offering both a blocking and an async API can then write it
+/// once, and let a feature choose between them.
+pub(crate) fn expand(
+    config: &AsyncBounds,
+    args: &proc_macro2::TokenStream,
+    input: TokenStream,
+    cfg: &Meta,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let config = AsyncBounds {
+        maybe_async: None,
+        ..config.clone()
+    };
+    let item = MaybeAsync::parse(input.clone())?;
+
+    // Warnings only apply to the async version
+    let desugared = crate::expand(&config, args, input)?;
+    let warnings = diagnostic::take_warnings();
+    let desugared = quote! { #desugared #(#warnings)* };
+
+    let not_cfg: Attribute = syn::parse_quote! { #[cfg(not(#cfg))] };
+    let desugared = if item.is_method() {
+        with_attribute::<ImplItem>(desugared, &not_cfg)?
+    } else {
+        with_attribute::<Item>(desugared, &not_cfg)?
+    };
+
+    let mut synchronous = item;
+    synchronous.strip()?;
+    Ok(quote! {
+        #[cfg(#cfg)]
+        #synchronous
+        #desugared
+    })
+}
+
+/// What `maybe_async` can be applied to.
+enum MaybeAsync {
+    Trait(ItemTrait),
+    Impl(ItemImpl),
+    Method(ImplItemFn),
+}
+
+impl MaybeAsync {
+    fn parse(input: TokenStream) -> syn::Result<Self> {
+        if let Ok(trait_item) = syn::parse::<ItemTrait>(input.clone()) {
+            Ok(MaybeAsync::Trait(trait_item))
+        } else if let Ok(impl_item) = syn::parse::<ItemImpl>(input.clone()) {
+            Ok(MaybeAsync::Impl(impl_item))
+        } else if let Ok(impl_fn) = syn::parse::<ImplItemFn>(input)
+            && (impl_fn.sig.receiver().is_some() || impl_fn.defaultness.is_some())
+        {
+            Ok(MaybeAsync::Method(impl_fn))
+        } else {
+            Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "`maybe_async` can only be applied to a trait, an impl block, or a method in one",
+            ))
+        }
+    }
+
+    fn is_method(&self) -> bool {
+        matches!(self, MaybeAsync::Method(_))
+    }
+
+    /// Remove every `async` and `.await`, and the attributes bitte would
+    /// have handled.
+    fn strip(&mut self) -> syn::Result<()> {
+        let mut strip = Strip { result: Ok(()) };
+        match self {
+            MaybeAsync::Trait(item) => strip.visit_item_trait_mut(item),
+            MaybeAsync::Impl(item) => strip.visit_item_impl_mut(item),
+            MaybeAsync::Method(item) => strip.visit_impl_item_fn_mut(item),
+        }
+        strip.result
+    }
+}
+
+impl ToTokens for MaybeAsync {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self {
+            MaybeAsync::Trait(item) => item.to_tokens(tokens),
+            MaybeAsync::Impl(item) => item.to_tokens(tokens),
+            MaybeAsync::Method(item) => item.to_tokens(tokens),
+        }
+    }
+}
+
+/// Add `attr` to each item in `tokens`.
+fn with_attribute<T: Parse + ToTokens + HasAttributes>(
+    tokens: proc_macro2::TokenStream,
+    attr: &Attribute,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let parser = |input: ParseStream| {
+        let mut items = Vec::new();
+        while !input.is_empty() {
+            items.push(input.parse::<T>()?);
+        }
+        Ok(items)
+    };
+    let mut items = parser.parse2(tokens)?;
+    for item in &mut items {
+        if let Some(attrs) = item.attrs_mut() {
+            attrs.insert(0, attr.clone());
+        }
+    }
+    Ok(quote! { #(#items)* })
+}
+
+trait HasAttributes {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>>;
+}
+
+impl HasAttributes for Item {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            Item::Const(item) => Some(&mut item.attrs),
+            Item::Enum(item) => Some(&mut item.attrs),
+            Item::ExternCrate(item) => Some(&mut item.attrs),
+            Item::Fn(item) => Some(&mut item.attrs),
+            Item::ForeignMod(item) => Some(&mut item.attrs),
+            Item::Impl(item) => Some(&mut item.attrs),
+            Item::Macro(item) => Some(&mut item.attrs),
+            Item::Mod(item) => Some(&mut item.attrs),
+            Item::Static(item) => Some(&mut item.attrs),
+            Item::Struct(item) => Some(&mut item.attrs),
+            Item::Trait(item) => Some(&mut item.attrs),
+            Item::TraitAlias(item) => Some(&mut item.attrs),
+            Item::Type(item) => Some(&mut item.attrs),
+            Item::Union(item) => Some(&mut item.attrs),
+            Item::Use(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+impl HasAttributes for ImplItem {
+    fn attrs_mut(&mut self) -> Option<&mut Vec<Attribute>> {
+        match self {
+            ImplItem::Const(item) => Some(&mut item.attrs),
+            ImplItem::Fn(item) => Some(&mut item.attrs),
+            ImplItem::Type(item) => Some(&mut item.attrs),
+            ImplItem::Macro(item) => Some(&mut item.attrs),
+            _ => None,
+        }
+    }
+}
+
+/// Turns async code into the same code, run synchronously.
+struct Strip {
+    result: syn::Result<()>,
+}
+
+impl VisitMut for Strip {
+    fn visit_signature_mut(&mut self, sig: &mut Signature) {
+        sig.asyncness = None;
+        if let Err(e) = strip_skips(sig) {
+            combine_error(&mut self.result, e);
+        }
+        visit_mut::visit_signature_mut(self, sig);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, method: &mut ImplItemFn) {
+        method.attrs.retain(|attr| !is_bitte_attribute(attr));
+        visit_mut::visit_impl_item_fn_mut(self, method);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, method: &mut TraitItemFn) {
+        method.attrs.retain(|attr| !is_bitte_attribute(attr));
+        visit_mut::visit_trait_item_fn_mut(self, method);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            // The awaited expression may be async itself
+            Expr::Await(await_expr) => {
+                *expr = (*await_expr.base).clone();
+                return self.visit_expr_mut(expr);
+            }
+            // An `async` block runs its statements in place
+            Expr::Async(async_expr) => {
+                *expr = Expr::Block(ExprBlock {
+                    attrs: async_expr.attrs.clone(),
+                    label: None,
+                    block: async_expr.block.clone(),
+                });
+            }
+            // And an `async` closure returns its body’s value
+            Expr::Closure(closure) => closure.asyncness = None,
+            _ => {}
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        mac.tokens = strip_await(std::mem::take(&mut mac.tokens));
+    }
+}
+
+/// Remove `.await` from the tokens of a macro invocation, such as
+/// `println!("{}", read().await)`, which aren’t parsed as expressions.
+fn strip_await(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut stripped = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct)
+                if punct.as_char() == '.'
+                    && matches!(tokens.peek(), Some(TokenTree::Ident(ident)) if ident == "await") =>
+            {
+                tokens.next();
+            }
+            TokenTree::Group(group) => {
+                let mut stripped_group =
+                    proc_macro2::Group::new(group.delimiter(), strip_await(group.stream()));
+                stripped_group.set_span(group.span());
+                stripped.push(TokenTree::Group(stripped_group));
+            }
+            token => stripped.push(token),
+        }
+    }
+    stripped.into_iter().collect()
+}
@@ -0,0 +1,175 @@
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    GenericParam, Generics, Ident, ImplItem, Lifetime, ReturnType, Signature, TraitItem, Type,
+    TypeParamBound, WherePredicate, parse_quote,
+};
+
+use crate::add_where_predicate;
+use crate::boxed::Lifetimes;
+use crate::variant::has_impl_trait_argument;
+
+/// The associated type naming the future of a desugared method:
+///
+/// ```ignore
+/// type GetUserFuture<'future>: Future<Output = User> + Send
+/// where
+///     Self: 'future;
+/// ```
+pub(crate) struct NamedFuture {
+    pub(crate) ident: Ident,
+    params: Vec<GenericParam>,
+    predicates: Vec<WherePredicate>,
+    bounds: Vec<TypeParamBound>,
+}
+
+impl NamedFuture {
+    /// Declare the type in a trait, bounded like the method’s future.
+    pub(crate) fn to_trait_item(&self) -> TraitItem {
+        let NamedFuture {
+            ident,
+            params,
+            predicates,
+            bounds,
+        } = self;
+
+        parse_quote! {
+            type #ident<#(#params),*>: #(#bounds)+*
+            where
+                #(#predicates),*;
+        }
+    }
+
+    /// Define the type in an impl, as a boxed future.
+    pub(crate) fn to_impl_item(&self) -> ImplItem {
+        let NamedFuture {
+            ident,
+            params,
+            predicates,
+            bounds,
+        } = self;
+
+        // A trait object can only have one lifetime bound
+        let lifetime = future_lifetime();
+        let lifetime = (!bounds
+            .iter()
+            .any(|bound| matches!(bound, TypeParamBound::Lifetime(_))))
+        .then(|| quote! { + #lifetime });
+
+        parse_quote! {
+            type #ident<#(#params),*> = std::pin::Pin<Box<dyn #(#bounds)+* #lifetime>>
+            where
+                #(#predicates),*;
+        }
+    }
+}
+
+fn future_lifetime() -> Lifetime {
+    Lifetime::new("'future", Span::call_site())
+}
+
+/// Rewrite a desugared method to return `Self::{Method}Future<'future, ..>`
+/// instead of `impl Future`, returning the associated type it names.
+///
+/// Every elided lifetime in the arguments becomes `'future`, so one lifetime
+/// parameter covers everything the future borrows. `outer` are the generics
+/// of the enclosing trait or impl.
+pub(crate) fn name_future(sig: &mut Signature, outer: &Generics) -> syn::Result<NamedFuture> {
+    let ReturnType::Type(_, output) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            &sig.ident,
+            "expected a desugared `async fn`",
+        ));
+    };
+    let Type::ImplTrait(impl_trait) = &**output else {
+        return Err(syn::Error::new_spanned(
+            output,
+            "expected a desugared `async fn`",
+        ));
+    };
+    let bounds: Vec<TypeParamBound> = impl_trait.bounds.iter().cloned().collect();
+
+    if has_impl_trait_argument(sig) {
+        return Err(syn::Error::new_spanned(
+            &sig.inputs,
+            "a named future can't capture `impl Trait` arguments; use a generic parameter",
+        ));
+    }
+
+    let lifetime = future_lifetime();
+    let mut lifetimes = Lifetimes::unified(lifetime.clone());
+    lifetimes.visit_inputs(&mut sig.inputs, &lifetime);
+
+    // The future is generic over everything the method is
+    let mut params: Vec<GenericParam> = vec![parse_quote! { #lifetime }];
+    let mut args = vec![quote! { #lifetime }];
+    let mut predicates: Vec<WherePredicate> = vec![parse_quote! { Self: #lifetime }];
+    for param in &sig.generics.params {
+        match param {
+            GenericParam::Lifetime(param) => {
+                let name = &param.lifetime;
+                params.push(parse_quote! { #name });
+                args.push(quote! { #name });
+                predicates.push(parse_quote! { #name: #lifetime });
+            }
+            GenericParam::Type(param) => {
+                let name = &param.ident;
+                params.push(parse_quote! { #name });
+                args.push(quote! { #name });
+                predicates.push(parse_quote! { #name: #lifetime });
+            }
+            GenericParam::Const(param) => {
+                let (name, ty) = (&param.ident, &param.ty);
+                params.push(parse_quote! { const #name: #ty });
+                args.push(quote! { #name });
+            }
+        }
+    }
+
+    // Lifetimes and types of the trait or impl, which the future captures
+    for name in &lifetimes.explicit {
+        let declared = sig
+            .generics
+            .lifetimes()
+            .any(|param| param.lifetime == *name);
+        if !declared {
+            predicates.push(parse_quote! { #name: #lifetime });
+        }
+    }
+    for param in outer.type_params() {
+        let name = &param.ident;
+        predicates.push(parse_quote! { #name: #lifetime });
+    }
+
+    sig.generics.params.insert(0, parse_quote! { #lifetime });
+    for predicate in &predicates {
+        add_where_predicate(sig, predicate.clone());
+    }
+
+    let ident = future_ident(&sig.ident);
+    sig.output = parse_quote! { -> Self::#ident<#(#args),*> };
+
+    Ok(NamedFuture {
+        ident,
+        params,
+        predicates,
+        bounds,
+    })
+}
+
+/// `get_user` → `GetUserFuture`
+fn future_ident(method: &Ident) -> Ident {
+    let name = method.to_string();
+    let name = name.strip_prefix("r#").unwrap_or(&name);
+
+    let mut camel = String::new();
+    for word in name.split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+
+    format_ident!("{}Future", camel, span = method.span())
+}
@@ -0,0 +1,232 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens, format_ident, quote};
+use syn::{
+    FnArg, GenericParam, Ident, ItemTrait, ReturnType, TraitItem, TraitItemFn, Type, parse_quote,
+};
+
+use crate::{AsyncBounds, DesugarAsync, Receiver, Setting, combine_error, turbofish};
+
+/// Desugar a provided `*_owned` method for each `&self` async method of a
+/// trait, which takes `self: Arc<Self>` and forwards to the borrowed one,
+/// so the future it returns is `'static`.
+///
+/// A method whose other arguments or output borrow can’t have a `'static`
+/// future, and is left without one.
+///
+/// With `spawn`, each also gets a `spawn_*` method (or `spawn_local_*`, if
+/// its future isn’t [`Send`]), which spawns the owned future on Tokio.
+pub(crate) fn expand(
+    item: &ItemTrait,
+    config: &AsyncBounds,
+) -> syn::Result<Vec<(Ident, Vec<TraitItemFn>)>> {
+    let mut result = Ok(());
+    let mut methods = Vec::new();
+
+    for trait_item in &item.items {
+        let TraitItem::Fn(method) = trait_item else {
+            continue;
+        };
+        if method.sig.asyncness.is_none() || !borrows_self(method) || borrows_elsewhere(method) {
+            continue;
+        }
+
+        let mut attrs = method.attrs.clone();
+        match config.for_method(&mut attrs).and_then(|config| {
+            if config.skip {
+                return Ok(None);
+            }
+            owned_method(item, method, &config).map(Some)
+        }) {
+            Ok(Some(owned)) => methods.push((method.sig.ident.clone(), owned)),
+            Ok(None) => {}
+            Err(e) => combine_error(&mut result, e),
+        }
+    }
+
+    result.map(|()| methods)
+}
+
+fn owned_method(
+    item: &ItemTrait,
+    method: &TraitItemFn,
+    config: &AsyncBounds,
+) -> syn::Result<Vec<TraitItemFn>> {
+    let mut sig = method.sig.clone();
+    let ident = &method.sig.ident;
+    sig.ident = format_ident!("{}_owned", ident);
+
+    // Owning an `Arc<Self>`, the future is `Send` when the borrowed one is
+    let send = config
+        .resolve(Receiver::from_inputs(&method.sig.inputs).as_ref())?
+        .send;
+    let mut config = AsyncBounds {
+        send: Setting::explicit(send.enabled),
+        supertraits: false,
+        boxed: false,
+        named: false,
+        ..config.clone()
+    };
+    config.future.push(parse_quote! { 'static });
+    config.predicates.push(parse_quote! { Self: 'static });
+
+    // Everything else the future holds has to be `'static` too
+    let params = item
+        .generics
+        .params
+        .iter()
+        .chain(&method.sig.generics.params);
+    for param in params {
+        if let GenericParam::Type(param) = param {
+            let param = &param.ident;
+            config.predicates.push(parse_quote! { #param: 'static });
+        }
+    }
+
+    // Name every argument, to pass them along
+    let mut args = Vec::new();
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                *receiver = parse_quote! { self: std::sync::Arc<Self> };
+                args.push(quote! { &*self });
+            }
+            FnArg::Typed(arg) => {
+                let ident = Ident::new(&format!("__arg{i}"), Span::mixed_site());
+                *arg.pat = parse_quote! { #ident };
+                if let Type::ImplTrait(impl_trait) = &mut *arg.ty {
+                    impl_trait.bounds.push(parse_quote! { 'static });
+                }
+                args.push(quote! { #ident });
+            }
+        }
+    }
+
+    let turbofish = turbofish(&method.sig.generics);
+    let mut call = quote! { Self::#ident #turbofish(#(#args),*) };
+    if sig.unsafety.is_some() {
+        call = quote! { unsafe { #call } };
+    }
+
+    let doc = format!(
+        "Like [`Self::{ident}`], but owning `self`, so the future is `'static` and can be \
+         spawned."
+    );
+    let cfgs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+    let mut owned: TraitItemFn = parse_quote! {
+        #[doc = #doc]
+        #(#cfgs)*
+        #sig {
+            #call.await
+        }
+    };
+    owned.desugar_async(&config)?;
+
+    if !config.spawn {
+        return Ok(vec![owned]);
+    }
+    let spawn = spawn_method(method, &owned, send.enabled);
+    Ok(vec![owned, spawn])
+}
+
+/// A method spawning an owned future on Tokio, with the same arguments.
+fn spawn_method(method: &TraitItemFn, owned: &TraitItemFn, send: bool) -> TraitItemFn {
+    let ident = &method.sig.ident;
+    let (spawn, name) = if send {
+        (
+            quote! { ::tokio::task::spawn },
+            format_ident!("spawn_{}", ident),
+        )
+    } else {
+        (
+            quote! { ::tokio::task::spawn_local },
+            format_ident!("spawn_local_{}", ident),
+        )
+    };
+
+    let mut sig = owned.sig.clone();
+    sig.ident = name;
+
+    let output = match &method.sig.output {
+        ReturnType::Default => quote! { () },
+        ReturnType::Type(_, ty) => quote! { #ty },
+    };
+    sig.output = parse_quote! { -> ::tokio::task::JoinHandle<#output> };
+    let predicate = if send {
+        parse_quote! { #output: Send + 'static }
+    } else {
+        parse_quote! { #output: 'static }
+    };
+    sig.generics.make_where_clause().predicates.push(predicate);
+
+    let args = sig.inputs.iter().map(|input| match input {
+        FnArg::Receiver(receiver) => receiver.self_token.to_token_stream(),
+        FnArg::Typed(arg) => arg.pat.to_token_stream(),
+    });
+    let turbofish = turbofish(&method.sig.generics);
+    let owned_ident = &owned.sig.ident;
+    let mut call = quote! { Self::#owned_ident #turbofish(#(#args),*) };
+    if sig.unsafety.is_some() {
+        call = quote! { unsafe { #call } };
+    }
+
+    let function = if send { "spawn" } else { "spawn_local" };
+    let doc =
+        format!("Spawn [`Self::{ident}`] as a new Tokio task, with `tokio::task::{function}`.");
+    let cfgs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"));
+    parse_quote! {
+        #[doc = #doc]
+        #(#cfgs)*
+        #sig {
+            #spawn(#call)
+        }
+    }
+}
+
+/// Whether a method takes `&self`.
+fn borrows_self(method: &TraitItemFn) -> bool {
+    matches!(
+        method.sig.inputs.first(),
+        Some(FnArg::Receiver(receiver))
+            if receiver.colon_token.is_none()
+                && receiver.reference.is_some()
+                && receiver.mutability.is_none()
+    )
+}
+
+/// Whether a method’s arguments (other than `self`) or output borrow
+/// anything, or it has lifetime parameters.
+fn borrows_elsewhere(method: &TraitItemFn) -> bool {
+    fn borrows(tokens: TokenStream) -> bool {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let is_static =
+            |i: usize| matches!(tokens.get(i), Some(TokenTree::Ident(ident)) if ident == "static");
+        tokens.iter().enumerate().any(|(i, token)| match token {
+            // Any reference or lifetime but `'static`
+            TokenTree::Punct(punct) if punct.as_char() == '&' => {
+                !matches!(tokens.get(i + 1), Some(TokenTree::Punct(next)) if next.as_char() == '\'')
+                    || !is_static(i + 2)
+            }
+            TokenTree::Punct(punct) if punct.as_char() == '\'' => !is_static(i + 1),
+            TokenTree::Group(group) => borrows(group.stream()),
+            _ => false,
+        })
+    }
+
+    let sig = &method.sig;
+    let args = sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(arg) => Some(arg.ty.to_token_stream()),
+        FnArg::Receiver(_) => None,
+    });
+    let output = match &sig.output {
+        ReturnType::Default => TokenStream::new(),
+        ReturnType::Type(_, ty) => ty.to_token_stream(),
+    };
+
+    sig.generics.lifetimes().next().is_some() || args.chain([output]).any(borrows)
+}
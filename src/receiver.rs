@@ -0,0 +1,247 @@
+use syn::{
+    FnArg, GenericArgument, Path, PathArguments, Type, TypeParamBound, parse_quote,
+    punctuated::Punctuated,
+};
+
+/// How an `async fn` takes `self`, which decides what `Self` must implement
+/// for the returned future to be [`Send`].
+///
+/// Receivers are classified structurally, so nested pointers like
+/// `Pin<Arc<Self>>` or `&Arc<Self>` get the same treatment as the types
+/// they’re built from:
+///
+/// | Receiver     | `Send` requires     | `Sync` requires     |
+/// | ------------ | ------------------- | ------------------- |
+/// | `Self`       | `Self: Send`        | `Self: Sync`        |
+/// | `&T`         | `T: Sync`           | `T: Sync`           |
+/// | `&mut T`     | `T: Send`           | `T: Sync`           |
+/// | `Box<T>`     | `T: Send`           | `T: Sync`           |
+/// | `Pin<T>`     | `T: Send`           | `T: Sync`           |
+/// | `Arc<T>`     | `T: Send + Sync`    | `T: Send + Sync`    |
+/// | `Rc<T>`      | never               | never               |
+#[derive(Debug, Clone)]
+pub(crate) struct Receiver {
+    /// The receiver type as written, for error reporting
+    pub(crate) ty: Type,
+    /// Bounds on `Self` for the receiver to be [`Send`], or `None` if it
+    /// never can be
+    send: Option<SelfBounds>,
+    /// Whether other references to `Self` may exist alongside the receiver
+    shared: bool,
+    /// Whether the receiver is itself a shared reference, like `&self`
+    borrowed: bool,
+}
+
+/// The bounds applied to a desugared `async fn` because of its receiver,
+/// even when they weren’t configured.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct ReceiverBounds {
+    pub(crate) needs_send: bool,
+    pub(crate) needs_sync: bool,
+}
+
+/// A set of auto trait bounds on `Self`.
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub(crate) struct SelfBounds {
+    pub(crate) send: bool,
+    pub(crate) sync: bool,
+}
+
+impl SelfBounds {
+    pub(crate) const SEND: SelfBounds = SelfBounds {
+        send: true,
+        sync: false,
+    };
+    pub(crate) const SYNC: SelfBounds = SelfBounds {
+        send: false,
+        sync: true,
+    };
+
+    pub(crate) fn union(self, other: SelfBounds) -> SelfBounds {
+        SelfBounds {
+            send: self.send || other.send,
+            sync: self.sync || other.sync,
+        }
+    }
+
+    pub(crate) fn to_bounds(self) -> Vec<TypeParamBound> {
+        let mut bounds = Vec::new();
+        if self.send {
+            bounds.push(parse_quote! { Send });
+        }
+        if self.sync {
+            bounds.push(parse_quote! { Sync });
+        }
+        bounds
+    }
+}
+
+/// The pointer types which can wrap `Self` in a receiver.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub(crate) enum Pointer {
+    Box,
+    Pin,
+    Arc,
+    Rc,
+}
+
+impl Pointer {
+    /// Recognize `Box`, `Pin`, `Arc`, and `Rc` by name, optionally
+    /// qualified by their module in `std`, `core`, or `alloc`.
+    pub(crate) fn from_path(path: &Path) -> Option<(Pointer, &Type)> {
+        let segments: Vec<_> = path.segments.iter().collect();
+        let (last, prefix) = segments.split_last()?;
+
+        let (pointer, module) = match last.ident.to_string().as_str() {
+            "Box" => (Pointer::Box, "boxed"),
+            "Pin" => (Pointer::Pin, "pin"),
+            "Arc" => (Pointer::Arc, "sync"),
+            "Rc" => (Pointer::Rc, "rc"),
+            _ => return None,
+        };
+
+        let qualified = match prefix {
+            [] => true,
+            [krate, m] => {
+                matches!(krate.ident.to_string().as_str(), "std" | "core" | "alloc")
+                    && m.ident == module
+            }
+            _ => false,
+        };
+        if !qualified || prefix.iter().any(|s| !s.arguments.is_none()) {
+            return None;
+        }
+
+        let PathArguments::AngleBracketed(args) = &last.arguments else {
+            return None;
+        };
+        // `Box` and `Arc` may name an allocator as a second argument
+        match args.args.first()? {
+            GenericArgument::Type(inner) => Some((pointer, inner)),
+            _ => None,
+        }
+    }
+}
+
+impl Receiver {
+    /// Classify the receiver of a function, if it has one.
+    pub(crate) fn from_inputs(inputs: &Punctuated<FnArg, syn::Token![,]>) -> Option<Self> {
+        let Some(FnArg::Receiver(receiver)) = inputs.first() else {
+            return None;
+        };
+
+        let ty = (*receiver.ty).clone();
+        let requirements = Requirements::of(&ty);
+
+        Some(Receiver {
+            send: requirements.send,
+            shared: requirements.shared,
+            borrowed: is_borrowed(&ty),
+            ty,
+        })
+    }
+
+    /// Whether the receiver can ever be sent to another thread.
+    pub(crate) fn can_send(&self) -> bool {
+        self.send.is_some()
+    }
+
+    /// The bounds applied for this receiver even when not configured.
+    pub(crate) fn inferred_bounds(&self) -> ReceiverBounds {
+        ReceiverBounds {
+            // Futures which own (or exclusively borrow) their receiver are
+            // expected to move between threads
+            needs_send: self.can_send() && !self.borrowed,
+            // Receivers which share `Self` are expected to be shared
+            // between threads
+            needs_sync: self.can_send() && self.shared,
+        }
+    }
+
+    /// The minimal bounds on `Self` for the future to be [`Send`] (when
+    /// `send` is set), and for a shared receiver to be [`Sync`] (when `sync`
+    /// is set).
+    pub(crate) fn self_bounds(&self, send: bool, sync: bool) -> SelfBounds {
+        let mut bounds = SelfBounds::default();
+
+        if send && let Some(required) = self.send {
+            bounds = bounds.union(required);
+        }
+        if sync && self.shared && self.can_send() {
+            bounds = bounds.union(SelfBounds::SYNC);
+        }
+
+        bounds
+    }
+}
+
+/// Whether a receiver type is a shared reference, looking through `Pin`.
+fn is_borrowed(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => reference.mutability.is_none(),
+        Type::Group(group) => is_borrowed(&group.elem),
+        Type::Paren(paren) => is_borrowed(&paren.elem),
+        Type::Path(path) if path.qself.is_none() => match Pointer::from_path(&path.path) {
+            Some((Pointer::Pin, inner)) => is_borrowed(inner),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// What `Self` must implement for a receiver type to be [`Send`] or
+/// [`Sync`].
+struct Requirements {
+    send: Option<SelfBounds>,
+    sync: Option<SelfBounds>,
+    shared: bool,
+}
+
+impl Requirements {
+    fn of(ty: &Type) -> Self {
+        match ty {
+            Type::Reference(reference) => {
+                let inner = Requirements::of(&reference.elem);
+
+                if reference.mutability.is_some() {
+                    inner
+                } else {
+                    Requirements {
+                        send: inner.sync,
+                        sync: inner.sync,
+                        shared: true,
+                    }
+                }
+            }
+            Type::Group(group) => Requirements::of(&group.elem),
+            Type::Paren(paren) => Requirements::of(&paren.elem),
+            Type::Path(path) if path.qself.is_none() => match Pointer::from_path(&path.path) {
+                Some((Pointer::Box | Pointer::Pin, inner)) => Requirements::of(inner),
+                Some((Pointer::Arc, inner)) => {
+                    let inner = Requirements::of(inner);
+                    let both = inner.send.zip(inner.sync).map(|(a, b)| a.union(b));
+
+                    Requirements {
+                        send: both,
+                        sync: both,
+                        shared: true,
+                    }
+                }
+                Some((Pointer::Rc, _)) => Requirements {
+                    send: None,
+                    sync: None,
+                    shared: true,
+                },
+                // `Self`, or the implementing type named directly
+                None => Requirements::SELF,
+            },
+            _ => Requirements::SELF,
+        }
+    }
+
+    const SELF: Requirements = Requirements {
+        send: Some(SelfBounds::SEND),
+        sync: Some(SelfBounds::SYNC),
+        shared: false,
+    };
+}
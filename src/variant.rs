@@ -0,0 +1,186 @@
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{FnArg, Ident, ImplItem, ItemTrait, Pat, Signature, TraitItem, parse_quote};
+
+use crate::{
+    AsyncBound, AsyncBounds, Setting, Variant, companion, desugar_trait_methods, turbofish,
+};
+
+/// Expand a trait into a local version without any bounds, and a variant
+/// with the bounds `variant` asks for, which the local version is
+/// implemented for.
+pub(crate) fn expand(
+    item: &mut ItemTrait,
+    config: &AsyncBounds,
+    variant: &Variant,
+) -> syn::Result<TokenStream> {
+    let mut bounded = config.clone();
+    bounded.variant = None;
+    for bound in &variant.bounds {
+        match *bound {
+            AsyncBound::Send(enabled) => bounded.send = Setting::explicit(enabled),
+            AsyncBound::Sync(enabled) => bounded.sync = Setting::explicit(enabled),
+        }
+    }
+
+    // The local trait’s methods take the same arguments as the variant’s, so
+    // that it can forward to them
+    let mut local = bounded.clone();
+    local.send = Setting::explicit(false);
+    local.sync = Setting::explicit(false);
+    local.supertraits = false;
+    local.send_arguments = true;
+
+    let mut variant_trait = item.clone();
+    variant_trait.ident = variant.ident.clone();
+    let variant_companion = companion::expand(&variant_trait, &bounded);
+    let local_companion = companion::expand(item, &local);
+    let self_bounds = desugar_trait_methods(&mut variant_trait, &bounded)?;
+    if bounded.supertraits {
+        for bound in self_bounds.to_bounds() {
+            variant_trait.supertraits.push(bound);
+        }
+    }
+
+    desugar_trait_methods(item, &local)?;
+
+    // Implement the local trait for every implementor of the variant
+    let blanket = format_ident!("BitteVariant");
+    let (_, ty_generics, where_clause) = item.generics.split_for_impl();
+    let local_ident = &item.ident;
+    let variant_ident = &variant.ident;
+    let variant_path = quote! { <#blanket as #variant_ident #ty_generics> };
+
+    let mut impl_generics = item.generics.clone();
+    impl_generics.params.push(parse_quote! { #blanket });
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+
+    let mut predicates = where_clause
+        .map(|clause| clause.predicates.iter().cloned().collect())
+        .unwrap_or_else(Vec::new);
+    let self_bounds = self_bounds.to_bounds();
+    predicates.push(parse_quote! {
+        #blanket: #variant_ident #ty_generics #(+ #self_bounds)*
+    });
+
+    let mut items = Vec::new();
+    for trait_item in &item.items {
+        items.push(forward(trait_item, &variant_path)?);
+    }
+
+    let unsafety = &item.unsafety;
+    Ok(quote! {
+        #item
+        #local_companion
+
+        #variant_trait
+        #variant_companion
+
+        #unsafety impl #impl_generics #local_ident #ty_generics for #blanket
+        where
+            #(#predicates,)*
+        {
+            #(#items)*
+        }
+    })
+}
+
+/// Implement a trait item by forwarding it to the variant.
+fn forward(item: &TraitItem, variant: &TokenStream) -> syn::Result<ImplItem> {
+    match item {
+        TraitItem::Const(constant) => {
+            let (ident, ty) = (&constant.ident, &constant.ty);
+            Ok(parse_quote! {
+                const #ident: #ty = #variant::#ident;
+            })
+        }
+        TraitItem::Type(assoc) => {
+            let ident = &assoc.ident;
+            let (impl_generics, ty_generics, where_clause) = assoc.generics.split_for_impl();
+            Ok(parse_quote! {
+                type #ident #impl_generics = #variant::#ident #ty_generics #where_clause;
+            })
+        }
+        TraitItem::Fn(method) => {
+            let mut sig = method.sig.clone();
+            let args = forwarded_arguments(&mut sig);
+            let ident = &sig.ident;
+
+            // Generic arguments can’t be given explicitly alongside
+            // `impl Trait` arguments
+            let turbofish = if has_impl_trait_argument(&sig) {
+                None
+            } else {
+                turbofish(&sig.generics)
+            };
+
+            let call = quote! { #variant::#ident #turbofish(#(#args),*) };
+            let body = if sig.asyncness.is_some() {
+                quote! { #call.await }
+            } else {
+                call
+            };
+
+            Ok(parse_quote! {
+                #[inline]
+                #[allow(
+                    clippy::multiple_bound_locations,
+                    clippy::type_complexity,
+                    clippy::type_repetition_in_bounds
+                )]
+                #sig {
+                    #body
+                }
+            })
+        }
+        other => Err(syn::Error::new_spanned(
+            other,
+            "`variant` can only forward methods, associated types, and constants",
+        )),
+    }
+}
+
+/// Name every argument of a forwarding method, returning the names to pass
+/// to the variant.
+pub(crate) fn forwarded_arguments(sig: &mut Signature) -> Vec<Ident> {
+    let mut args = Vec::new();
+
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            FnArg::Receiver(receiver) => {
+                // `mut self` only matters to the body
+                if receiver.reference.is_none() {
+                    receiver.mutability = None;
+                }
+                args.push(Ident::new("self", receiver.self_token.span));
+            }
+            FnArg::Typed(arg) => {
+                let ident = match &*arg.pat {
+                    Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                        pat.ident.clone()
+                    }
+                    _ => format_ident!("__arg{}", i),
+                };
+                arg.pat = parse_quote! { #ident };
+                args.push(ident);
+            }
+        }
+    }
+
+    args
+}
+
+pub(crate) fn has_impl_trait_argument(sig: &Signature) -> bool {
+    fn contains_impl(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            TokenTree::Ident(ident) => ident == "impl",
+            TokenTree::Group(group) => contains_impl(group.stream()),
+            _ => false,
+        })
+    }
+
+    sig.inputs.iter().any(|input| match input {
+        FnArg::Typed(arg) => contains_impl(quote! { #arg }),
+        FnArg::Receiver(_) => false,
+    })
+}
@@ -0,0 +1,163 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::Cell;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A typed key, which is `Send` and `Sync` whatever it points at.
+struct Key<T>(&'static str, PhantomData<fn() -> T>);
+
+impl<T> Key<T> {
+    fn new(name: &'static str) -> Self {
+        Key(name, PhantomData)
+    }
+}
+
+// Generic and `impl Trait` arguments get the bounds the future needs
+#[bitte(Send, Sync)]
+trait Store {
+    async fn put<T: Debug>(&self, key: &str, value: &T) -> String;
+    async fn put_owned<T: Debug>(&self, value: T) -> String;
+    async fn put_shared<T: Debug>(&self, value: Arc<T>) -> String;
+    async fn put_all<T: Debug>(&self, values: Vec<&mut T>) -> usize;
+    async fn put_display(&self, value: impl Display) -> String;
+    async fn put_display_ref(&self, value: &impl Display) -> String;
+
+    // A shared `Mutex<T>` only needs `T: Send`, and a `RwLock<T>` more
+    async fn take<T: Debug + Default>(&self, value: &Mutex<T>) -> String;
+    async fn read<T: Debug>(&self, value: &RwLock<T>) -> String;
+
+    // Opted out, since `Key<T>` is `Send` even when `T` isn't
+    async fn lookup<T>(&self, #[bitte(skip)] key: Key<T>) -> &'static str;
+    async fn lookup_all<#[bitte(skip)] T>(&self, keys: Vec<Key<T>>) -> usize;
+}
+
+struct MemoryStore;
+
+#[bitte(Send, Sync)]
+impl Store for MemoryStore {
+    async fn put<T: Debug>(&self, key: &str, value: &T) -> String {
+        tokio::task::yield_now().await;
+        format!("{key}={value:?}")
+    }
+
+    async fn put_owned<T: Debug>(&self, value: T) -> String {
+        tokio::task::yield_now().await;
+        format!("{value:?}")
+    }
+
+    async fn put_shared<T: Debug>(&self, value: Arc<T>) -> String {
+        tokio::task::yield_now().await;
+        format!("{value:?}")
+    }
+
+    async fn put_all<T: Debug>(&self, values: Vec<&mut T>) -> usize {
+        tokio::task::yield_now().await;
+        values.len()
+    }
+
+    async fn put_display(&self, value: impl Display) -> String {
+        tokio::task::yield_now().await;
+        value.to_string()
+    }
+
+    async fn put_display_ref(&self, value: &impl Display) -> String {
+        tokio::task::yield_now().await;
+        value.to_string()
+    }
+
+    async fn take<T: Debug + Default>(&self, value: &Mutex<T>) -> String {
+        tokio::task::yield_now().await;
+        format!("{:?}", std::mem::take(&mut *value.lock().unwrap()))
+    }
+
+    async fn read<T: Debug>(&self, value: &RwLock<T>) -> String {
+        tokio::task::yield_now().await;
+        format!("{:?}", *value.read().unwrap())
+    }
+
+    async fn lookup<T>(&self, #[bitte(skip)] key: Key<T>) -> &'static str {
+        tokio::task::yield_now().await;
+        key.0
+    }
+
+    async fn lookup_all<#[bitte(skip)] T>(&self, keys: Vec<Key<T>>) -> usize {
+        tokio::task::yield_now().await;
+        keys.len()
+    }
+}
+
+#[bitte(Send)]
+async fn describe<T: Debug>(value: &T) -> String {
+    tokio::task::yield_now().await;
+    format!("{value:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_generic_arguments() {
+        let store = MemoryStore;
+
+        let future = store.put("answer", &42);
+        assert_send(&future);
+        assert_eq!(future.await, "answer=42");
+
+        assert_eq!(store.put_owned(vec![1, 2]).await, "[1, 2]");
+        assert_eq!(store.put_shared(Arc::new("shared")).await, "\"shared\"");
+        assert_eq!(store.put_all(vec![&mut 1, &mut 2]).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_impl_trait_arguments() {
+        let store = MemoryStore;
+
+        let future = store.put_display(7);
+        assert_send(&future);
+        assert_eq!(future.await, "7");
+        assert_eq!(store.put_display_ref(&"ref").await, "ref");
+    }
+
+    #[tokio::test]
+    async fn test_lock_arguments() {
+        let store = MemoryStore;
+
+        // `Cell` is `Send` but not `Sync`
+        let cell = Mutex::new(Cell::new(3));
+        let future = store.take(&cell);
+        assert_send(&future);
+        assert_eq!(future.await, "Cell { value: 3 }");
+        assert_eq!(cell.lock().unwrap().get(), 0);
+
+        assert_eq!(store.read(&RwLock::new(5)).await, "5");
+    }
+
+    #[tokio::test]
+    async fn test_skipped_parameters() {
+        let store = MemoryStore;
+
+        let future = store.lookup(Key::<Rc<u8>>::new("local"));
+        assert_send(&future);
+        assert_eq!(future.await, "local");
+
+        let keys = vec![Key::<Rc<u8>>::new("a"), Key::new("b")];
+        assert_eq!(store.lookup_all(keys).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_free_function_arguments() {
+        let value = String::from("spawned");
+        let described = tokio::spawn(async move { describe(&value).await })
+            .await
+            .unwrap();
+
+        assert_eq!(described, "\"spawned\"");
+    }
+}
@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::fmt::Display;
+use std::sync::Mutex;
+
+// Left as an `async fn`, but checked to be `Send`
+#[bitte(assert(Send))]
+pub async fn fetch(id: u64) -> String {
+    tokio::task::yield_now().await;
+    format!("item-{id}")
+}
+
+// Checked wherever the arguments are `Send`, as `#[bitte(Send)]` would require
+#[bitte(assert(Send))]
+pub async fn describe<T: Display>(value: T) -> String {
+    tokio::task::yield_now().await;
+    value.to_string()
+}
+
+#[derive(Default)]
+pub struct Client {
+    requests: Mutex<u64>,
+}
+
+#[bitte(assert(Send))]
+impl Client {
+    pub async fn new() -> Self {
+        Client::default()
+    }
+
+    pub async fn get(&self, path: &str) -> String {
+        *self.requests.lock().unwrap() += 1;
+        fetch(path.len() as u64).await
+    }
+
+    pub fn requests(&self) -> u64 {
+        *self.requests.lock().unwrap()
+    }
+}
+
+pub struct Cache;
+
+impl Cache {
+    #[bitte(assert(Send + Sync))]
+    pub async fn lookup(&self, key: String) -> Option<String> {
+        tokio::task::yield_now().await;
+        Some(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_asserted_functions_spawn() {
+        assert_eq!(tokio::spawn(fetch(1)).await.unwrap(), "item-1");
+        assert_eq!(tokio::spawn(describe(2)).await.unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn test_asserted_methods_spawn() {
+        let client = Arc::new(Client::new().await);
+        let spawned = Arc::clone(&client);
+        let body = tokio::spawn(async move { spawned.get("/users").await })
+            .await
+            .unwrap();
+        assert_eq!(body, "item-6");
+        assert_eq!(client.requests(), 1);
+
+        assert_eq!(Cache.lookup("a".to_string()).await.as_deref(), Some("a"));
+    }
+}
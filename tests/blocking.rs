@@ -0,0 +1,134 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// `ConfigBlocking` calls each method from synchronous code
+#[bitte(Send, Sync, blocking)]
+pub trait Config {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn set<V: ToString>(&mut self, key: String, value: V);
+    async fn load(self) -> usize;
+    fn path(&self) -> &str;
+}
+
+#[derive(Default)]
+struct MemoryConfig {
+    values: HashMap<String, String>,
+}
+
+#[bitte]
+impl Config for MemoryConfig {
+    async fn get(&self, key: &str) -> Option<String> {
+        Woken::default().await;
+        self.values.get(key).cloned()
+    }
+
+    async fn set<V: ToString>(&mut self, key: String, value: V) {
+        self.values.insert(key, value.to_string());
+    }
+
+    async fn load(self) -> usize {
+        self.values.len()
+    }
+
+    fn path(&self) -> &str {
+        "memory"
+    }
+}
+
+/// A future which is pending once, waking itself from another thread.
+#[derive(Default)]
+struct Woken {
+    spawned: bool,
+}
+
+impl std::future::Future for Woken {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.spawned {
+            return std::task::Poll::Ready(());
+        }
+        self.spawned = true;
+        let waker = cx.waker().clone();
+        std::thread::spawn(move || waker.wake());
+        std::task::Poll::Pending
+    }
+}
+
+static BLOCKED: Mutex<usize> = Mutex::new(0);
+
+/// A `block_on` counting how many futures it ran.
+fn counting_block_on<F: std::future::Future>(future: F) -> F::Output {
+    *BLOCKED.lock().unwrap() += 1;
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+// Or run the futures with any `block_on` function
+#[bitte(?Send, ?Sync, blocking(counting_block_on))]
+pub trait Greeter {
+    async fn greet(&self, name: String) -> String;
+}
+
+impl Greeter for MemoryConfig {
+    async fn greet(&self, name: String) -> String {
+        tokio::task::yield_now().await;
+        format!("hello, {name}")
+    }
+}
+
+#[bitte(blocking)]
+pub trait Source<T>
+where
+    T: Clone,
+{
+    async fn fetch(&self) -> T;
+}
+
+struct Fixed<T>(T);
+
+#[bitte]
+impl<T: Clone> Source<T> for Fixed<T> {
+    async fn fetch(&self) -> T {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_methods() {
+        let mut config = MemoryConfig::default();
+        config.set_blocking("name".to_string(), "bitte");
+        config.set_blocking("size".to_string(), 2);
+
+        assert_eq!(config.get_blocking("name").as_deref(), Some("bitte"));
+        assert_eq!(config.get_blocking("missing"), None);
+        assert_eq!(config.path(), "memory");
+        assert_eq!(config.load_blocking(), 2);
+        assert_eq!(Fixed(7).fetch_blocking(), 7);
+    }
+
+    #[test]
+    fn test_custom_block_on() {
+        let config = MemoryConfig::default();
+        assert_eq!(config.greet_blocking("world".to_string()), "hello, world");
+        assert_eq!(*BLOCKED.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_methods_with_both_traits_in_scope() {
+        let config = MemoryConfig::default();
+        assert_eq!(config.greet("async".to_string()).await, "hello, async");
+    }
+}
@@ -0,0 +1,137 @@
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use bitte::bitte;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+// A trait defined with async-trait, implemented with bitte
+#[async_trait]
+pub trait Fetch {
+    async fn fetch(&self, key: &str, fallback: &str) -> String;
+    async fn fetch_display<T: Display + Send + Sync>(&self, value: &T) -> String;
+    async fn fetch_impl(&self, value: impl Display + Send + 'async_trait) -> String;
+
+    async fn fetch_twice(&self, key: &str) -> String {
+        format!("{}{}", self.fetch(key, "").await, self.fetch(key, "").await)
+    }
+}
+
+struct Upper;
+
+#[bitte(boxed)]
+impl Fetch for Upper {
+    async fn fetch(&self, key: &str, fallback: &str) -> String {
+        tokio::task::yield_now().await;
+        if key.is_empty() {
+            fallback.to_uppercase()
+        } else {
+            key.to_uppercase()
+        }
+    }
+
+    async fn fetch_display<T: Display + Send + Sync>(&self, value: &T) -> String {
+        value.to_string().to_uppercase()
+    }
+
+    async fn fetch_impl(&self, value: impl Display + Send) -> String {
+        value.to_string().to_uppercase()
+    }
+}
+
+// A trait defined with bitte, implemented with async-trait
+#[bitte(boxed)]
+pub trait Store {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn put(&mut self, key: String, value: String);
+
+    async fn get_or(&self, key: &str, default: &str) -> String {
+        self.get(key).await.unwrap_or_else(|| default.to_string())
+    }
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn get(&self, key: &str) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    async fn put(&mut self, key: String, value: String) {
+        self.entries.get_mut().unwrap().insert(key, value);
+    }
+}
+
+// `boxed_local` matches `#[async_trait(?Send)]`
+#[bitte(boxed_local)]
+trait LocalStore {
+    async fn get(&self, key: &str) -> Option<String>;
+}
+
+struct RcStore {
+    entries: Rc<RefCell<HashMap<String, String>>>,
+}
+
+#[async_trait(?Send)]
+impl LocalStore for RcStore {
+    async fn get(&self, key: &str) -> Option<String> {
+        let entries = Rc::clone(&self.entries);
+        tokio::task::yield_now().await;
+        entries.borrow().get(key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_bitte_impl_of_async_trait() {
+        let fetcher = Upper;
+        assert_eq!(fetcher.fetch("abc", "").await, "ABC");
+        assert_eq!(fetcher.fetch("", "fallback").await, "FALLBACK");
+        assert_eq!(fetcher.fetch_twice("ab").await, "ABAB");
+        assert_eq!(fetcher.fetch_display(&42).await, "42");
+        assert_eq!(fetcher.fetch_impl("x").await, "X");
+
+        let future = fetcher.fetch("send", "");
+        assert_send(&future);
+        assert_eq!(future.await, "SEND");
+    }
+
+    #[tokio::test]
+    async fn test_async_trait_impl_of_bitte() {
+        let mut store = MemoryStore::default();
+        store.put("a".to_string(), "1".to_string()).await;
+
+        // Boxed traits are `dyn`-compatible
+        let store: Arc<dyn Store + Send + Sync> = Arc::new(store);
+        assert_eq!(store.get_or("a", "0").await, "1");
+        assert_eq!(store.get_or("b", "0").await, "0");
+
+        let handle = tokio::spawn(async move { store.get("a").await });
+        assert_eq!(handle.await.unwrap().as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_boxed_local() {
+        let store = RcStore {
+            entries: Rc::new(RefCell::new(HashMap::from([(
+                "a".to_string(),
+                "1".to_string(),
+            )]))),
+        };
+        let store: Box<dyn LocalStore> = Box::new(store);
+        assert_eq!(store.get("a").await.as_deref(), Some("1"));
+    }
+}
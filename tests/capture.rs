@@ -0,0 +1,41 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::future::Future;
+
+pub struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    #[bitte]
+    pub async fn first(&self) -> Option<char> {
+        self.input.chars().next()
+    }
+
+    // Only the future of a method which borrows nothing can outlive `'a`
+    #[bitte(capture())]
+    pub async fn version() -> u32 {
+        2
+    }
+}
+
+fn detached<'a>(_parser: &Parser<'a>) -> impl Future<Output = u32> + 'static {
+    Parser::<'a>::version()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_capture_nothing() {
+        let future = {
+            let input = String::from("bitte");
+            let parser = Parser { input: &input };
+            assert_eq!(parser.first().await, Some('b'));
+            detached(&parser)
+        };
+        assert_eq!(future.await, 2);
+    }
+}
@@ -0,0 +1,62 @@
+//! This crate’s `Cargo.toml` sets `threads = false`, which takes precedence
+//! over bitte’s `threads` feature.
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::future::ready;
+use std::rc::Rc;
+
+// Holding an `Rc` across an await is only allowed without `Send`
+#[bitte]
+pub trait Local {
+    async fn get(&self) -> u32;
+}
+
+pub struct Value(u32);
+
+#[bitte]
+impl Local for Value {
+    async fn get(&self) -> u32 {
+        let local = Rc::new(self.0);
+        ready(()).await;
+        *local
+    }
+}
+
+pub struct Doubled(u32);
+
+// Desugared with this crate’s defaults too, as the trait was
+#[bitte(trait)]
+impl Local for Doubled {
+    async fn get(&self) -> u32 {
+        let local = Rc::new(self.0);
+        double(*local).await
+    }
+}
+
+#[bitte]
+pub async fn double(value: u32) -> u32 {
+    let local = Rc::new(value * 2);
+    ready(()).await;
+    *local
+}
+
+// Checked with this crate's defaults, though the signature is left as is
+#[bitte(assert(Send))]
+pub async fn tripled(value: u32) -> u32 {
+    ready(()).await;
+    value * 3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_crate_config_overrides_feature() {
+        assert_eq!(Value(1).get().await, 1);
+        assert_eq!(Doubled(3).get().await, 6);
+        assert_eq!(double(2).await, 4);
+        assert_eq!(tripled(2).await, 6);
+    }
+}
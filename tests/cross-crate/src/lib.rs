@@ -0,0 +1,44 @@
+//! `Local` is declared in a crate which opts out of the `threads` feature's
+//! bounds, while this crate doesn't, so its impls only match the trait by
+//! taking the trait's configuration.
+#![allow(dead_code)]
+
+use bitte::bitte;
+use bitte_crate_config::Local;
+use std::future::ready;
+use std::rc::Rc;
+
+pub struct Counter(u32);
+
+// Found through the imported trait
+#[bitte]
+impl Local for Counter {
+    async fn get(&self) -> u32 {
+        let local = Rc::new(self.0 + 1);
+        ready(()).await;
+        *local
+    }
+}
+
+pub struct Squared(u32);
+
+// Or through the trait's path
+#[bitte]
+impl bitte_crate_config::Local for Squared {
+    async fn get(&self) -> u32 {
+        let local = Rc::new(self.0 * self.0);
+        ready(()).await;
+        *local
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_impl_of_foreign_trait() {
+        assert_eq!(Counter(1).get().await, 2);
+        assert_eq!(Squared(3).get().await, 9);
+    }
+}
@@ -0,0 +1,244 @@
+//! Compares when arguments are dropped by native `async fn`s, and by the
+//! same functions desugared by bitte.
+#![allow(dead_code, clippy::toplevel_ref_arg)]
+
+use bitte::bitte;
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+/// Records when it is dropped.
+struct Guard {
+    name: &'static str,
+    log: Log,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.log.lock().unwrap().push(self.name);
+    }
+}
+
+struct Pair {
+    left: Guard,
+    right: Guard,
+}
+
+fn guard(log: &Log, name: &'static str) -> Guard {
+    Guard {
+        name,
+        log: log.clone(),
+    }
+}
+
+fn body(log: &Log, local: Guard) {
+    log.lock().unwrap().push("body");
+    drop(local);
+}
+
+// Free functions, with every kind of pattern
+
+#[bitte]
+async fn patterns_bitte(
+    _: Guard,
+    (a, _): (Guard, Guard),
+    Pair { right, .. }: Pair,
+    ref _borrowed: Guard,
+    mut named: Guard,
+    log: Log,
+) {
+    let _local = guard(&log, "local");
+    named.name = "named";
+    tokio::task::yield_now().await;
+    body(&log, guard(&log, "temporary"));
+    let _ = (&a, &right);
+}
+
+async fn patterns_native(
+    _: Guard,
+    (a, _): (Guard, Guard),
+    Pair { right, .. }: Pair,
+    ref _borrowed: Guard,
+    mut named: Guard,
+    log: Log,
+) {
+    let _local = guard(&log, "local");
+    named.name = "named";
+    tokio::task::yield_now().await;
+    body(&log, guard(&log, "temporary"));
+    let _ = (&a, &right);
+}
+
+fn pattern_arguments(log: &Log) -> (Guard, (Guard, Guard), Pair, Guard, Guard) {
+    (
+        guard(log, "underscore"),
+        (guard(log, "tuple.0"), guard(log, "tuple.1")),
+        Pair {
+            left: guard(log, "pair.left"),
+            right: guard(log, "pair.right"),
+        },
+        guard(log, "borrowed"),
+        guard(log, "unnamed"),
+    )
+}
+
+// Methods of a trait, in an impl block and in a default body
+
+#[bitte]
+trait Hold {
+    async fn hold(&self, _: Guard, tuple: (Guard, Guard), log: Log);
+
+    async fn hold_default(&self, _: Guard, (_, b): (Guard, Guard), log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+        let _ = &b;
+    }
+
+    async fn consume(self, _: Guard, log: Log);
+}
+
+trait HoldNative {
+    async fn hold(&self, _: Guard, tuple: (Guard, Guard), log: Log);
+
+    async fn hold_default(&self, _: Guard, (_, b): (Guard, Guard), log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+        let _ = &b;
+    }
+
+    async fn consume(self, _: Guard, log: Log);
+}
+
+struct Holder(Guard);
+
+#[bitte]
+impl Hold for Holder {
+    async fn hold(&self, _: Guard, (a, b): (Guard, Guard), log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+        let _ = (&a, &b);
+    }
+
+    async fn consume(self, _: Guard, log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+    }
+}
+
+impl HoldNative for Holder {
+    async fn hold(&self, _: Guard, (a, b): (Guard, Guard), log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+        let _ = (&a, &b);
+    }
+
+    async fn consume(self, _: Guard, log: Log) {
+        tokio::task::yield_now().await;
+        body(&log, guard(&log, "temporary"));
+    }
+}
+
+fn pair(log: &Log) -> (Guard, (Guard, Guard)) {
+    (
+        guard(log, "underscore"),
+        (guard(log, "tuple.0"), guard(log, "tuple.1")),
+    )
+}
+
+/// Run `f`, returning the order everything was dropped in.
+async fn order<F: Future<Output = ()>>(log: &Log, f: impl FnOnce(Log) -> F) -> Vec<&'static str> {
+    f(log.clone()).await;
+    std::mem::take(&mut *log.lock().unwrap())
+}
+
+#[tokio::test]
+async fn test_pattern_arguments() {
+    let log = Log::default();
+
+    let bitte = order(&log, |log| {
+        let (underscore, tuple, pair, borrowed, named) = pattern_arguments(&log);
+        patterns_bitte(underscore, tuple, pair, borrowed, named, log)
+    })
+    .await;
+    let native = order(&log, |log| {
+        let (underscore, tuple, pair, borrowed, named) = pattern_arguments(&log);
+        patterns_native(underscore, tuple, pair, borrowed, named, log)
+    })
+    .await;
+
+    assert_eq!(bitte, native);
+}
+
+#[tokio::test]
+async fn test_method_arguments() {
+    let log = Log::default();
+    let holder = Holder(guard(&log, "holder"));
+
+    let bitte = order(&log, |log| {
+        let (underscore, tuple) = pair(&log);
+        Hold::hold(&holder, underscore, tuple, log)
+    })
+    .await;
+    let native = order(&log, |log| {
+        let (underscore, tuple) = pair(&log);
+        HoldNative::hold(&holder, underscore, tuple, log)
+    })
+    .await;
+    assert_eq!(bitte, native);
+
+    let bitte = order(&log, |log| {
+        let (underscore, tuple) = pair(&log);
+        Hold::hold_default(&holder, underscore, tuple, log)
+    })
+    .await;
+    let native = order(&log, |log| {
+        let (underscore, tuple) = pair(&log);
+        HoldNative::hold_default(&holder, underscore, tuple, log)
+    })
+    .await;
+    assert_eq!(bitte, native);
+}
+
+#[tokio::test]
+async fn test_self_by_value() {
+    let log = Log::default();
+
+    let bitte = order(&log, |log| {
+        Hold::consume(Holder(guard(&log, "self")), guard(&log, "underscore"), log)
+    })
+    .await;
+    let native = order(&log, |log| {
+        HoldNative::consume(Holder(guard(&log, "self")), guard(&log, "underscore"), log)
+    })
+    .await;
+    assert_eq!(bitte, native);
+}
+
+#[tokio::test]
+async fn test_dropped_before_polling() {
+    let log = Log::default();
+
+    let (underscore, tuple, pair, borrowed, named) = pattern_arguments(&log);
+    drop(patterns_bitte(
+        underscore,
+        tuple,
+        pair,
+        borrowed,
+        named,
+        log.clone(),
+    ));
+    let bitte = std::mem::take(&mut *log.lock().unwrap());
+
+    let (underscore, tuple, pair, borrowed, named) = pattern_arguments(&log);
+    drop(patterns_native(
+        underscore,
+        tuple,
+        pair,
+        borrowed,
+        named,
+        log.clone(),
+    ));
+    let native = std::mem::take(&mut *log.lock().unwrap());
+
+    assert_eq!(bitte, native);
+}
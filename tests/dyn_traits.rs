@@ -0,0 +1,126 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Emits `Cache`, with `impl Future` methods, and `DynCache`, with boxed ones
+#[bitte(Send, Sync, dyn)]
+pub trait Cache {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn insert(&mut self, key: &str, value: String);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Default)]
+struct MemoryCache {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+#[bitte(Send, Sync)]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    async fn insert(&mut self, key: &str, value: String) {
+        tokio::task::yield_now().await;
+        self.entries
+            .get_mut()
+            .unwrap()
+            .insert(key.to_string(), value);
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+// Accepts anything implementing `Cache`, including trait objects
+async fn lookup<C: Cache + Sync + ?Sized>(cache: &C, key: &str) -> Option<String> {
+    cache.get(key).await
+}
+
+// Trait generics carry over, and methods keep their per-method bounds
+#[bitte(dyn)]
+trait Source<T> {
+    async fn next(&mut self) -> Option<T>;
+
+    #[bitte(Send)]
+    async fn peek(&self, offset: usize) -> Option<T>;
+}
+
+struct Counter(u32);
+
+#[bitte]
+impl Source<u32> for Counter {
+    async fn next(&mut self) -> Option<u32> {
+        self.0 += 1;
+        Some(self.0)
+    }
+
+    #[bitte(Send)]
+    async fn peek(&self, offset: usize) -> Option<u32> {
+        Some(self.0 + offset as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_trait_object() {
+        // `DynCache`'s methods have names of their own, so calls with both
+        // traits in scope aren't ambiguous
+        let mut cache = MemoryCache::default();
+        cache.insert("a", "1".to_string()).await;
+
+        let cache: Arc<dyn DynCache> = Arc::new(cache);
+        let future = cache.get_dyn("a");
+        assert_send(&future);
+        assert_eq!(future.await.as_deref(), Some("1"));
+        assert_eq!(cache.len_dyn(), 1);
+
+        // And the object implements `Cache`
+        let future = cache.get("a");
+        assert_send(&future);
+        assert_eq!(future.await.as_deref(), Some("1"));
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.is_empty());
+
+        // The future is `Send`, so it can be spawned
+        let spawned = Arc::clone(&cache);
+        let handle = tokio::spawn(async move { spawned.get("a").await });
+        assert_eq!(handle.await.unwrap().as_deref(), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_object_implements_trait() {
+        let cache: Arc<dyn DynCache> = Arc::new(MemoryCache::default());
+        assert_eq!(lookup(&*cache, "missing").await, None);
+
+        let mut boxed: Box<dyn DynCache> = Box::new(MemoryCache::default());
+        boxed.insert("b", "2".to_string()).await;
+        assert_eq!(lookup(&boxed, "b").await.as_deref(), Some("2"));
+        assert_eq!(boxed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_generic_trait_object() {
+        let mut source: Box<dyn DynSource<u32>> = Box::new(Counter(0));
+        assert_eq!(source.next_dyn().await, Some(1));
+        assert_eq!(source.next().await, Some(2));
+
+        let future = source.peek_dyn(1);
+        assert_send(&future);
+        assert_eq!(future.await, Some(3));
+    }
+}
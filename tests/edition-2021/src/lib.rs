@@ -0,0 +1,109 @@
+//! Until edition 2024, `-> impl Future` only captures the lifetimes it
+//! names, but the tokens bitte generates carry its own edition, so their
+//! futures capture every lifetime in scope in this crate too.
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[bitte]
+pub async fn first_word(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or_default()
+}
+
+#[bitte]
+pub async fn longest<'a>(a: &'a str, b: &str) -> usize {
+    a.len().max(b.len())
+}
+
+#[bitte]
+pub async fn describe(value: &impl Display, suffix: &str) -> String {
+    format!("{value}{suffix}")
+}
+
+pub struct Cache<'a, T> {
+    prefix: &'a str,
+    entries: HashMap<String, T>,
+}
+
+#[bitte]
+impl<'a, T: Clone + Send> Cache<'a, T> {
+    pub async fn get(&self, key: &str) -> Option<T> {
+        self.entries.get(&format!("{}{key}", self.prefix)).cloned()
+    }
+
+    pub async fn insert(&mut self, key: &str, value: T) {
+        self.entries.insert(format!("{}{key}", self.prefix), value);
+    }
+
+    async fn prefix(&self) -> &'a str {
+        self.prefix
+    }
+}
+
+pub struct Counter {
+    name: String,
+    count: u32,
+}
+
+impl Counter {
+    #[bitte]
+    pub async fn add(&mut self, amount: &u32) -> u32 {
+        self.count += amount;
+        self.count
+    }
+
+    #[bitte]
+    pub async fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+pub struct Wrapper<T> {
+    value: T,
+}
+
+impl<T: Clone + Sync> Wrapper<T> {
+    #[bitte]
+    pub async fn from_ref(value: &T) -> Self {
+        Wrapper {
+            value: value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_free_functions_capture_arguments() {
+        let text = String::from("hello world");
+        assert_eq!(first_word(&text).await, "hello");
+        assert_eq!(longest("a", "bcd").await, 3);
+        assert_eq!(describe(&1, "st").await, "1st");
+    }
+
+    #[tokio::test]
+    async fn test_methods_capture_arguments() {
+        let prefix = String::from("user:");
+        let mut cache = Cache {
+            prefix: &prefix,
+            entries: HashMap::new(),
+        };
+        cache.insert("alice", 1).await;
+        assert_eq!(cache.get("alice").await, Some(1));
+        assert_eq!(cache.prefix().await, "user:");
+
+        let mut counter = Counter {
+            name: "visits".to_string(),
+            count: 1,
+        };
+        assert_eq!(counter.add(&2).await, 3);
+        assert_eq!(counter.name().await, "visits");
+
+        let value = String::from("cloned");
+        assert_eq!(Wrapper::from_ref(&value).await.value, "cloned");
+    }
+}
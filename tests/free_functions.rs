@@ -0,0 +1,90 @@
+use bitte::bitte;
+use std::sync::{Arc, Mutex};
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+/// Records when it is dropped.
+struct Guard {
+    name: &'static str,
+    log: Log,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.log.lock().unwrap().push(self.name);
+    }
+}
+
+#[bitte(Send)]
+pub async fn fetch(url: String, retries: u32) -> usize {
+    tokio::task::yield_now().await;
+    url.len() * retries as usize
+}
+
+#[bitte]
+async fn fetch_with_mut(mut attempts: u32) -> u32 {
+    attempts += 1;
+    tokio::task::yield_now().await;
+    attempts
+}
+
+#[bitte]
+async fn hold_bitte(_first: Guard, _second: Guard, log: Log) {
+    log.lock().unwrap().push("body");
+}
+
+async fn hold_native(_first: Guard, _second: Guard, log: Log) {
+    log.lock().unwrap().push("body");
+}
+
+#[bitte(Send)]
+async fn borrow_generic<T: Sync>(value: &T) -> &T {
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guards(log: &Log) -> (Guard, Guard) {
+        let first = Guard {
+            name: "first",
+            log: log.clone(),
+        };
+        let second = Guard {
+            name: "second",
+            log: log.clone(),
+        };
+        (first, second)
+    }
+
+    #[tokio::test]
+    async fn test_free_function_body() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let future = fetch("https://example.com".to_string(), 2);
+        assert_send(&future);
+        assert_eq!(future.await, 38);
+
+        assert_eq!(fetch_with_mut(1).await, 2);
+        assert_eq!(*borrow_generic(&5).await, 5);
+    }
+
+    #[tokio::test]
+    async fn test_unused_arguments_live_as_long_as_the_future() {
+        let log = Log::default();
+        let (first, second) = guards(&log);
+
+        let future = hold_bitte(first, second, log.clone());
+        assert!(log.lock().unwrap().is_empty());
+        future.await;
+
+        let bitte_order = std::mem::take(&mut *log.lock().unwrap());
+
+        let (first, second) = guards(&log);
+        hold_native(first, second, log.clone()).await;
+        let native_order = std::mem::take(&mut *log.lock().unwrap());
+
+        assert_eq!(bitte_order, native_order);
+    }
+}
@@ -0,0 +1,163 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::future::Future;
+use std::sync::Arc;
+
+/// A scheduler that only accepts `'static + Send` futures with `Send` output.
+fn schedule<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future)
+}
+
+#[bitte(Send, + 'static, Output: Send + 'static)]
+trait Producer<T> {
+    async fn produce(self: Arc<Self>) -> T;
+}
+
+struct Numbers;
+
+#[bitte(Send, + 'static, Output: Send + 'static)]
+impl Producer<u32> for Numbers {
+    async fn produce(self: Arc<Self>) -> u32 {
+        tokio::task::yield_now().await;
+        42
+    }
+}
+
+// The future is `'static` and `Send` for any implementor, and `produce` is
+// only callable when its output can be sent back from the scheduler
+async fn produce_in_background<T, P>(producer: Arc<P>) -> T
+where
+    T: Send + 'static,
+    P: Producer<T> + Send + Sync + 'static,
+{
+    schedule(producer.produce()).await.unwrap()
+}
+
+// Bounds can be stacked in one argument, and the future itself made `Sync`
+#[bitte(+ Unpin + Sync)]
+trait Ready {
+    async fn value(&self) -> u32;
+}
+
+struct Immediate(u32);
+
+impl Ready for Immediate {
+    fn value(&self) -> impl Future<Output = u32> + Unpin + Sync {
+        std::future::ready(self.0)
+    }
+}
+
+#[bitte(+ Sync)]
+async fn shared_future(value: u32) -> u32 {
+    value
+}
+
+// Custom `where` predicates come last, and may have several clauses
+#[bitte(Send, where T: Clone + Send + Sync, Self: Sized)]
+trait Repository<T> {
+    async fn latest(&self) -> Option<T>;
+}
+
+struct Fixed<T>(T);
+
+#[bitte(Send, where T: Clone + Send + Sync, Self: Sized)]
+impl<T> Repository<T> for Fixed<T> {
+    async fn latest(&self) -> Option<T> {
+        Some(self.0.clone())
+    }
+}
+
+// A borrowed output is bounded on the future, where its elided lifetime is
+// in scope
+#[bitte(Send, Output: Send + Sync)]
+trait Named {
+    async fn name(&self) -> &str;
+}
+
+struct Label(String);
+
+#[bitte(Send, Output: Send + Sync)]
+impl Named for Label {
+    async fn name(&self) -> &str {
+        tokio::task::yield_now().await;
+        self.0.as_str()
+    }
+}
+
+#[bitte(Send, Output: Send)]
+async fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[bitte(boxed, Output: Send)]
+trait BoxedNamed {
+    async fn name(&self) -> &str;
+}
+
+#[bitte(boxed, Output: Send)]
+impl BoxedNamed for Label {
+    async fn name(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[bitte(Send, dyn, Output: Send)]
+trait Titled {
+    async fn name(&self) -> &str;
+}
+
+#[bitte(Send, Output: Send)]
+impl Titled for Label {
+    async fn name(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_sync<T: Sync>(_: &T) {}
+    fn assert_unpin<T: Unpin>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_static_future_with_send_output() {
+        assert_eq!(produce_in_background(Arc::new(Numbers)).await, 42);
+    }
+
+    #[tokio::test]
+    async fn test_extra_future_bounds() {
+        let immediate = Immediate(7);
+        let future = immediate.value();
+        assert_sync(&future);
+        assert_unpin(&future);
+        assert_eq!(future.await, 7);
+
+        let future = shared_future(3);
+        assert_sync(&future);
+        assert_eq!(future.await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_borrowed_output_bounds() {
+        let label = Label("label".to_string());
+        assert_eq!(Named::name(&label).await, "label");
+        assert_eq!(BoxedNamed::name(&label).await, "label");
+
+        let dyn_label: &dyn DynTitled = &label;
+        assert_eq!(dyn_label.name().await, "label");
+
+        assert_eq!(first_line("one\ntwo").await, "one");
+    }
+
+    #[tokio::test]
+    async fn test_where_predicates() {
+        let repository = Fixed("latest".to_string());
+        assert_eq!(repository.latest().await.as_deref(), Some("latest"));
+    }
+}
@@ -0,0 +1,136 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Declared without `Send`, with a helper trait for callers that need it
+#[bitte(?Send, ?Sync, named, helper(UserDatabaseSend))]
+pub trait UserDatabase {
+    async fn get_user(&self, id: u64) -> Option<String>;
+    async fn find(&self, name: &str) -> Vec<u64>;
+
+    // Opted out per method, so the helper doesn't bound it
+    #[bitte(?Send)]
+    async fn cursor(&self) -> Rc<Cell<u64>>;
+
+    // Generic methods can't be bounded for every type, so opt out too
+    #[bitte(?Send)]
+    async fn insert<T: Into<String>>(&self, id: u64, name: T);
+}
+
+struct SharedDatabase {
+    users: HashMap<u64, String>,
+}
+
+// `Send` futures make it a `UserDatabaseSend`
+#[bitte(Send, Sync, named)]
+impl UserDatabase for SharedDatabase {
+    async fn get_user(&self, id: u64) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.users.get(&id).cloned()
+    }
+
+    async fn find(&self, name: &str) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .users
+            .iter()
+            .filter(|(_, user)| *user == name)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[bitte(?Send)]
+    async fn cursor(&self) -> Rc<Cell<u64>> {
+        Rc::new(Cell::new(0))
+    }
+
+    #[bitte(?Send)]
+    async fn insert<T: Into<String>>(&self, _id: u64, _name: T) {}
+}
+
+// Holds an `Rc` across awaits, so it's only a `UserDatabase`
+struct LocalDatabase {
+    lookups: Rc<Cell<u64>>,
+}
+
+#[bitte(?Send, ?Sync, named)]
+impl UserDatabase for LocalDatabase {
+    async fn get_user(&self, id: u64) -> Option<String> {
+        let lookups = Rc::clone(&self.lookups);
+        tokio::task::yield_now().await;
+        lookups.set(lookups.get() + 1);
+        Some(format!("user-{id}"))
+    }
+
+    async fn find(&self, _name: &str) -> Vec<u64> {
+        Vec::new()
+    }
+
+    async fn cursor(&self) -> Rc<Cell<u64>> {
+        Rc::clone(&self.lookups)
+    }
+
+    async fn insert<T: Into<String>>(&self, _id: u64, _name: T) {}
+}
+
+// Generic code can spawn the futures of the helper's implementors
+async fn spawn_lookup<T>(database: Arc<T>, id: u64) -> Option<String>
+where
+    T: UserDatabaseSend + Send + Sync + 'static,
+{
+    tokio::spawn(async move { database.get_user(id).await })
+        .await
+        .unwrap()
+}
+
+async fn spawn_find<T>(database: Arc<T>, name: String) -> Vec<u64>
+where
+    T: UserDatabaseSend + Send + Sync + 'static,
+{
+    tokio::spawn(async move { database.find(&name).await })
+        .await
+        .unwrap()
+}
+
+async fn local_lookup<T: UserDatabase>(database: &T, id: u64) -> Option<String> {
+    database.get_user(id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_helper<T: UserDatabaseSend>() {}
+
+    #[tokio::test]
+    async fn test_spawn_helper_future() {
+        assert_helper::<SharedDatabase>();
+
+        let database = Arc::new(SharedDatabase {
+            users: HashMap::from([(1, "alice".to_string())]),
+        });
+        assert_eq!(
+            spawn_lookup(Arc::clone(&database), 1).await.as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            spawn_find(Arc::clone(&database), "alice".to_string()).await,
+            [1]
+        );
+        assert_eq!(local_lookup(&*database, 1).await.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_local_implementor() {
+        let database = LocalDatabase {
+            lookups: Rc::new(Cell::new(0)),
+        };
+        assert_eq!(local_lookup(&database, 2).await.as_deref(), Some("user-2"));
+        assert_eq!(database.cursor().await.get(), 1);
+    }
+}
@@ -127,6 +127,38 @@ impl<T: Clone + Send + Sync + 'static> GenericService<T> for GenericImpl {
     }
 }
 
+// Test `#[bitte]` on individual methods of impl blocks
+struct Client {
+    base: String,
+}
+
+impl Client {
+    #[bitte(Send)]
+    async fn get(&self, path: String) -> String {
+        tokio::time::sleep(tokio::time::Duration::from_millis(1)).await;
+        format!("{}/{}", self.base, path)
+    }
+
+    #[bitte(?Send)]
+    async fn get_local(&self) -> String {
+        let path = std::rc::Rc::new("local".to_string());
+        tokio::task::yield_now().await;
+        self.get(path.to_string()).await
+    }
+
+    #[bitte(Send)]
+    async fn new_async(base: String) -> Self {
+        Client { base }
+    }
+}
+
+impl ThreadSafeService for Client {
+    #[bitte(Send, Sync)]
+    async fn concurrent_process(&self) -> u32 {
+        self.get("count".to_string()).await.len() as u32
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +218,22 @@ mod tests {
         assert_eq!(num_result, 42);
     }
 
+    #[tokio::test]
+    async fn test_individual_impl_methods() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let client = Client::new_async("https://example.com".to_string()).await;
+
+        let future = client.get("users".to_string());
+        assert_send(&future);
+        assert_eq!(future.await, "https://example.com/users");
+        assert_eq!(client.get_local().await, "https://example.com/local");
+
+        let future = client.concurrent_process();
+        assert_send(&future);
+        assert_eq!(future.await, 25);
+    }
+
     // Test that Send bounds work correctly
     #[test]
     fn test_send_bounds() {
@@ -0,0 +1,213 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[bitte(Send, Sync)]
+trait UserDatabase {
+    async fn get_user(&self, id: u64) -> Option<String>;
+    async fn rename(&mut self, id: u64, name: String) -> bool;
+}
+
+#[derive(Default)]
+struct MemoryDatabase {
+    users: HashMap<u64, String>,
+}
+
+// Each body moves into an inner `async fn`, which `instrument` can wrap
+#[bitte(Send, Sync, inner)]
+impl UserDatabase for MemoryDatabase {
+    #[tracing::instrument(name = "get_user", skip(self))]
+    async fn get_user(&self, id: u64) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.users.get(&id).cloned()
+    }
+
+    #[inline]
+    #[cfg_attr(all(), allow(unused_mut))]
+    async fn rename(&mut self, id: u64, mut name: String) -> bool {
+        match self.users.get_mut(&id) {
+            Some(user) => {
+                std::mem::swap(user, &mut name);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[bitte(Send, Sync)]
+trait AuditLog {
+    async fn get_user(&self, id: u64) -> Option<String>;
+}
+
+// The inner functions of both impls share an inherent impl block's namespace
+#[bitte(Send, Sync, inner)]
+impl AuditLog for MemoryDatabase {
+    async fn get_user(&self, id: u64) -> Option<String> {
+        self.users.get(&id).map(|name| format!("looked up {name}"))
+    }
+}
+
+#[bitte(Send, Sync)]
+trait Describe {
+    type Label;
+
+    async fn describe(&self, label: Self::Label) -> String;
+}
+
+// A foreign type's inner functions go on a private extension trait, which
+// can name the trait's associated types
+#[bitte(Send, Sync, inner)]
+impl Describe for String {
+    type Label = u32;
+
+    #[tracing::instrument(name = "describe", skip_all)]
+    async fn describe(&self, label: Self::Label) -> String {
+        tokio::task::yield_now().await;
+        format!("{self} #{label}")
+    }
+}
+
+#[bitte(Send, Sync)]
+trait Shout {
+    async fn shout(&self) -> String;
+}
+
+// So do a blanket impl's
+#[bitte(Send, Sync, inner)]
+impl<T: std::fmt::Display + Sync> Shout for T {
+    #[tracing::instrument(name = "shout", skip_all)]
+    async fn shout(&self) -> String {
+        self.to_string().to_uppercase()
+    }
+}
+
+struct Client {
+    requests: Mutex<u64>,
+}
+
+impl Client {
+    /// Counts the request, in an inner `async fn` of its own
+    #[bitte(Send, inner)]
+    #[tracing::instrument(name = "fetch", skip_all)]
+    pub async fn fetch<T: ToString>(&self, (path, query): (T, &str)) -> String {
+        *self.requests.lock().unwrap() += 1;
+        tokio::task::yield_now().await;
+        format!("{}?{query}", path.to_string())
+    }
+}
+
+/// Counts how many times each span is entered.
+#[derive(Clone, Default)]
+struct Entered {
+    names: Arc<Mutex<Vec<&'static str>>>,
+    entered: Arc<Mutex<HashMap<&'static str, usize>>>,
+}
+
+impl Entered {
+    fn count(&self, name: &str) -> usize {
+        self.entered.lock().unwrap().get(name).copied().unwrap_or(0)
+    }
+}
+
+impl Subscriber for Entered {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.names.lock().unwrap();
+        names.push(span.metadata().name());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        let name = self.names.lock().unwrap()[span.into_u64() as usize - 1];
+        *self.entered.lock().unwrap().entry(name).or_default() += 1;
+    }
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[test]
+    fn test_instrument_spans_every_poll() {
+        let subscriber = Entered::default();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        tracing::subscriber::with_default(subscriber.clone(), || {
+            let mut database = MemoryDatabase::default();
+            database.users.insert(1, "alice".to_string());
+
+            let future = UserDatabase::get_user(&database, 1);
+            assert_send(&future);
+            assert_eq!(runtime.block_on(future).as_deref(), Some("alice"));
+
+            let client = Client {
+                requests: Mutex::new(0),
+            };
+            let future = client.fetch(("/users", "id=1"));
+            assert_send(&future);
+            assert_eq!(runtime.block_on(future), "/users?id=1");
+        });
+
+        // Entered each time the future was polled, not only to create it
+        assert!(subscriber.count("get_user") > 1);
+        assert!(subscriber.count("fetch") > 1);
+    }
+
+    #[tokio::test]
+    async fn test_attributes_on_inner_function() {
+        let mut database = MemoryDatabase::default();
+        database.users.insert(1, "alice".to_string());
+
+        assert!(database.rename(1, "bob".to_string()).await);
+        assert!(!database.rename(2, "carol".to_string()).await);
+        assert_eq!(
+            UserDatabase::get_user(&database, 1).await.as_deref(),
+            Some("bob")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_foreign_and_blanket_impls() {
+        let name = "alice".to_string();
+        let future = name.describe(1);
+        assert_send(&future);
+        assert_eq!(future.await, "alice #1");
+        assert_eq!(7.shout().await, "7");
+        assert_eq!("bob".shout().await, "BOB");
+    }
+
+    #[tokio::test]
+    async fn test_same_method_in_two_traits() {
+        let mut database = MemoryDatabase::default();
+        database.users.insert(1, "alice".to_string());
+
+        assert_eq!(
+            UserDatabase::get_user(&database, 1).await.as_deref(),
+            Some("alice")
+        );
+        assert_eq!(
+            AuditLog::get_user(&database, 1).await.as_deref(),
+            Some("looked up alice")
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! The same trait and impl, compiled as async code by default, and as
+//! blocking code with the `is_sync` feature, which `--all-features` enables.
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+
+#[bitte(Send, Sync, maybe_async)]
+pub trait Store {
+    async fn get(&self, key: &str) -> Option<u64>;
+    async fn put(&mut self, key: String, value: u64);
+
+    async fn get_or(&self, key: &str, default: u64) -> u64 {
+        self.get(key).await.unwrap_or(default)
+    }
+}
+
+#[derive(Default)]
+pub struct Memory {
+    values: HashMap<String, u64>,
+}
+
+#[bitte(maybe_async)]
+impl Store for Memory {
+    async fn get(&self, key: &str) -> Option<u64> {
+        // `async` blocks and closures are stripped along with `.await`
+        let value = async { self.values.get(key).copied() }.await?;
+        let double = async |value: u64| value * 2;
+        Some(double(value).await / 2)
+    }
+
+    async fn put(&mut self, key: String, value: u64) {
+        self.values.insert(key, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "is_sync"))]
+    #[tokio::test]
+    async fn test_async_version() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut store = Memory::default();
+        store.put("a".to_string(), 1).await;
+
+        let future = store.get("a");
+        assert_send(&future);
+        assert_eq!(future.await, Some(1));
+        assert_eq!(store.get_or("b", 2).await, 2);
+    }
+
+    #[cfg(feature = "is_sync")]
+    #[test]
+    fn test_sync_version() {
+        let mut store = Memory::default();
+        store.put("a".to_string(), 1);
+
+        assert_eq!(store.get("a"), Some(1));
+        assert_eq!(store.get_or("b", 2), 2);
+    }
+}
@@ -0,0 +1,109 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+
+// `any()` never holds, so this is desugared as usual
+#[bitte(Send, maybe_async(any()))]
+pub trait AsyncStore {
+    async fn get(&self, key: &str) -> Option<String>;
+    async fn put(&mut self, key: String, value: String);
+
+    async fn get_or(&self, key: &str, default: &str) -> String {
+        self.get(key).await.unwrap_or_else(|| default.to_string())
+    }
+}
+
+// While `all()` always holds, so every `async` and `.await` is stripped
+#[bitte(maybe_async(all()))]
+pub trait SyncStore {
+    async fn get(&self, key: &str) -> Option<String>;
+    #[bitte(?Send)]
+    async fn put(&mut self, key: String, value: String);
+
+    async fn get_or(&self, key: &str, default: &str) -> String {
+        self.get(key).await.unwrap_or_else(|| default.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Memory {
+    values: HashMap<String, String>,
+}
+
+#[bitte(maybe_async(any()))]
+impl AsyncStore for Memory {
+    async fn get(&self, key: &str) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.values.get(key).cloned()
+    }
+
+    async fn put(&mut self, key: String, value: String) {
+        self.values.insert(key, value);
+    }
+}
+
+struct Empty;
+
+// An impl deferring to the trait’s configuration takes `maybe_async` too
+#[bitte(trait, maybe_async(any()))]
+impl AsyncStore for Empty {
+    async fn get(&self, _key: &str) -> Option<String> {
+        None
+    }
+
+    async fn put(&mut self, _key: String, _value: String) {}
+}
+
+#[derive(Default)]
+struct Blocking {
+    values: HashMap<String, String>,
+}
+
+#[bitte(maybe_async(all()))]
+impl SyncStore for Blocking {
+    async fn get(&self, key: &str) -> Option<String> {
+        async { self.values.get(key) }.await.cloned()
+    }
+
+    async fn put(&mut self, key: String, value: String) {
+        assert!(!format!("{:?}", self.get(&key).await).is_empty());
+        self.values.insert(key, value);
+    }
+}
+
+impl Blocking {
+    #[bitte(maybe_async(all()))]
+    async fn len(&self, #[bitte(skip)] _unused: std::rc::Rc<()>) -> usize {
+        self.values.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[tokio::test]
+    async fn test_async_version() {
+        let mut store = Memory::default();
+        AsyncStore::put(&mut store, "name".to_string(), "bitte".to_string()).await;
+
+        let future = AsyncStore::get(&store, "name");
+        assert_send(AsyncStore::get(&store, "name"));
+        assert_eq!(future.await.as_deref(), Some("bitte"));
+        assert_eq!(AsyncStore::get_or(&store, "missing", "none").await, "none");
+        assert_send(AsyncStore::get_or(&Empty, "name", "none"));
+    }
+
+    #[test]
+    fn test_sync_version() {
+        let mut store = Blocking::default();
+        SyncStore::put(&mut store, "name".to_string(), "bitte".to_string());
+
+        assert_eq!(SyncStore::get(&store, "name").as_deref(), Some("bitte"));
+        assert_eq!(SyncStore::get_or(&store, "missing", "none"), "none");
+        assert_eq!(store.len(Default::default()), 1);
+    }
+}
@@ -0,0 +1,134 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::future::{Future, Ready, ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+// Each method's future is named by an associated type, like `GetUserFuture`
+#[bitte(Send, Sync, named)]
+pub trait UserDatabase {
+    async fn get_user(&self, id: u64) -> Option<String>;
+    async fn find(&self, name: &str) -> Vec<u64>;
+    async fn insert<T: Into<String>>(&mut self, id: u64, name: T);
+    fn count(&self) -> usize;
+}
+
+#[derive(Default)]
+struct MemoryDatabase {
+    users: HashMap<u64, String>,
+}
+
+// Impls box their futures by default
+#[bitte(Send, Sync, named)]
+impl UserDatabase for MemoryDatabase {
+    async fn get_user(&self, id: u64) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.users.get(&id).cloned()
+    }
+
+    async fn find(&self, name: &str) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .users
+            .iter()
+            .filter(|(_, user)| *user == name)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    async fn insert<T: Into<String>>(&mut self, id: u64, name: T) {
+        self.users.insert(id, name.into());
+    }
+
+    fn count(&self) -> usize {
+        self.users.len()
+    }
+}
+
+struct Anonymous;
+
+// Or name a concrete future, implementing the method without `async`
+#[bitte(Send, Sync, named)]
+impl UserDatabase for Anonymous {
+    type GetUserFuture<'future>
+        = Ready<Option<String>>
+    where
+        Self: 'future;
+
+    fn get_user<'future>(&'future self, _id: u64) -> Self::GetUserFuture<'future>
+    where
+        Self: 'future,
+    {
+        ready(Some("anonymous".to_string()))
+    }
+
+    async fn find(&self, _name: &str) -> Vec<u64> {
+        Vec::new()
+    }
+
+    async fn insert<T: Into<String>>(&mut self, _id: u64, _name: T) {}
+
+    fn count(&self) -> usize {
+        1
+    }
+}
+
+/// A future stored in a struct, which `impl Future` can't be named in.
+struct Lookup<'a, D: UserDatabase + 'a> {
+    future: D::GetUserFuture<'a>,
+}
+
+impl<'a, D: UserDatabase + 'a> Future for Lookup<'a, D>
+where
+    D::GetUserFuture<'a>: Unpin,
+{
+    type Output = String;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<String> {
+        Pin::new(&mut self.future)
+            .poll(cx)
+            .map(|user| user.unwrap_or_default())
+    }
+}
+
+fn lookup<D: UserDatabase + Sync>(database: &D, id: u64) -> Lookup<'_, D>
+where
+    for<'a> D::GetUserFuture<'a>: Unpin,
+{
+    Lookup {
+        future: database.get_user(id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_boxed_named_futures() {
+        let mut database = MemoryDatabase::default();
+        database.insert(1, "alice").await;
+        database.insert(2, "bob".to_string()).await;
+        database.insert(3, "alice").await;
+
+        let future: <MemoryDatabase as UserDatabase>::FindFuture<'_> = database.find("alice");
+        assert_send(&future);
+        assert_eq!(future.await, vec![1, 3]);
+
+        assert_eq!(lookup(&database, 2).await, "bob");
+        assert_eq!(lookup(&database, 4).await, "");
+    }
+
+    #[tokio::test]
+    async fn test_concrete_named_future() {
+        let database = Anonymous;
+        let future: Ready<Option<String>> = database.get_user(1);
+        assert_eq!(future.await.as_deref(), Some("anonymous"));
+        assert_eq!(lookup(&database, 1).await, "anonymous");
+    }
+}
@@ -0,0 +1,105 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::rc::Rc;
+
+// Method-level attributes refine the trait-wide configuration
+#[bitte(Send, Sync)]
+trait MostlyThreadSafe {
+    async fn shared(&self) -> u32;
+
+    #[bitte(?Send)]
+    async fn local_only(&self) -> u32;
+
+    #[bitte::bitte(?Send, ?Sync)]
+    async fn no_bounds(&self) -> u32;
+
+    // Left as a plain `async fn`
+    #[bitte(skip)]
+    async fn skipped(&self) -> u32;
+}
+
+struct Service;
+
+#[bitte(Send, Sync)]
+impl MostlyThreadSafe for Service {
+    async fn shared(&self) -> u32 {
+        1
+    }
+
+    #[bitte(?Send)]
+    async fn local_only(&self) -> u32 {
+        // Holding an `Rc` across an await is fine without `Send`
+        let value = Rc::new(2);
+        tokio::task::yield_now().await;
+        *value
+    }
+
+    #[bitte(?Send, ?Sync)]
+    async fn no_bounds(&self) -> u32 {
+        let value = Rc::new(3);
+        tokio::task::yield_now().await;
+        *value
+    }
+
+    #[bitte(skip)]
+    async fn skipped(&self) -> u32 {
+        4
+    }
+}
+
+// Nested attributes in an impl block
+#[bitte(Send)]
+trait Greeter {
+    async fn greet(&self) -> String;
+
+    #[bitte(?Send)]
+    async fn greet_locally(&self) -> String;
+}
+
+struct English;
+
+#[bitte(Send)]
+impl Greeter for English {
+    async fn greet(&self) -> String {
+        "hello".to_string()
+    }
+
+    #[bitte(?Send)]
+    async fn greet_locally(&self) -> String {
+        let greeting = Rc::new(self.greet().await);
+        tokio::task::yield_now().await;
+        format!("{greeting}, neighbor")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_nested_overrides() {
+        let service = Service;
+
+        let future = service.shared();
+        assert_send(&future);
+        assert_eq!(future.await, 1);
+
+        assert_eq!(service.local_only().await, 2);
+        assert_eq!(service.no_bounds().await, 3);
+        assert_eq!(service.skipped().await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_nested_impl_override() {
+        let english = English;
+
+        let future = english.greet();
+        assert_send(&future);
+        assert_eq!(future.await, "hello");
+
+        assert_eq!(english.greet_locally().await, "hello, neighbor");
+    }
+}
@@ -0,0 +1,77 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Explicit opt-outs win over what the receivers would otherwise infer, so
+// the trait can be implemented by types that are neither `Send` nor `Sync`
+#[bitte(?Send, ?Sync)]
+trait LocalStore {
+    async fn get(&self) -> u32;
+    async fn set(&mut self, value: u32);
+    async fn into_value(self) -> u32;
+    async fn get_shared(self: Arc<Self>) -> u32;
+    async fn get_counted(self: Rc<Self>) -> u32;
+}
+
+struct RcStore {
+    value: Rc<RefCell<u32>>,
+}
+
+#[bitte(?Send, ?Sync)]
+impl LocalStore for RcStore {
+    async fn get(&self) -> u32 {
+        tokio::task::yield_now().await;
+        *self.value.borrow()
+    }
+
+    async fn set(&mut self, value: u32) {
+        tokio::task::yield_now().await;
+        *self.value.borrow_mut() = value;
+    }
+
+    async fn into_value(self) -> u32 {
+        tokio::task::yield_now().await;
+        *self.value.borrow()
+    }
+
+    #[allow(clippy::arc_with_non_send_sync)]
+    async fn get_shared(self: Arc<Self>) -> u32 {
+        let value = self.value.clone();
+        tokio::task::yield_now().await;
+        *value.borrow()
+    }
+
+    async fn get_counted(self: Rc<Self>) -> u32 {
+        self.get().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(clippy::arc_with_non_send_sync)]
+    #[tokio::test]
+    async fn test_local_store() {
+        let mut store = RcStore {
+            value: Rc::new(RefCell::new(1)),
+        };
+
+        assert_eq!(store.get().await, 1);
+        store.set(2).await;
+        assert_eq!(store.into_value().await, 2);
+
+        let store = RcStore {
+            value: Rc::new(RefCell::new(3)),
+        };
+        assert_eq!(Arc::new(store).get_shared().await, 3);
+
+        let store = RcStore {
+            value: Rc::new(RefCell::new(4)),
+        };
+        assert_eq!(Rc::new(store).get_counted().await, 4);
+    }
+}
@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+// Each `&self` method gets an `*_owned` version taking `Arc<Self>`
+#[bitte(Send, Sync, owned)]
+pub trait Store {
+    async fn get(&self, key: String) -> Option<u64>;
+    async fn put<V: Into<u64>>(&self, key: String, value: V);
+    // Borrowing `key`, this future can't be `'static`, so has no owned version
+    async fn contains(&self, key: &str) -> bool;
+    async fn clear(&mut self);
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    values: Mutex<HashMap<String, u64>>,
+}
+
+#[bitte]
+impl Store for MemoryStore {
+    async fn get(&self, key: String) -> Option<u64> {
+        tokio::task::yield_now().await;
+        self.values.lock().unwrap().get(&key).copied()
+    }
+
+    async fn put<V: Into<u64>>(&self, key: String, value: V) {
+        self.values.lock().unwrap().insert(key, value.into());
+    }
+
+    async fn contains(&self, key: &str) -> bool {
+        self.values.lock().unwrap().contains_key(key)
+    }
+
+    async fn clear(&mut self) {
+        self.values.get_mut().unwrap().clear();
+    }
+}
+
+// A `?Send` trait's owned futures are `'static`, but not `Send`
+#[bitte(?Send, ?Sync, owned)]
+pub trait Local {
+    async fn name(&self) -> String;
+}
+
+impl Local for MemoryStore {
+    async fn name(&self) -> String {
+        "memory".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_static<T: Send + 'static>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_spawn_owned_futures() {
+        let store = Arc::new(MemoryStore::default());
+        Arc::clone(&store).put_owned("a".to_string(), 1u8).await;
+
+        let future = Arc::clone(&store).get_owned("a".to_string());
+        assert_static(&future);
+        let value = tokio::spawn(future).await.unwrap();
+        assert_eq!(value, Some(1));
+        assert!(store.contains("a").await);
+    }
+
+    #[tokio::test]
+    async fn test_local_owned_futures() {
+        let store = Arc::new(MemoryStore::default());
+        let local = tokio::task::LocalSet::new();
+        let name = local
+            .run_until(async { tokio::task::spawn_local(store.name_owned()).await })
+            .await
+            .unwrap();
+        assert_eq!(name, "memory");
+    }
+}
@@ -0,0 +1,181 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::Cell;
+use std::sync::Arc;
+
+// Each receiver gets the minimal `Self` bound for a `Send` future
+#[bitte(Send, Sync)]
+trait Counter {
+    async fn get(&self) -> u32;
+    async fn get_shared(self: Arc<Self>) -> u32;
+}
+
+// Exclusive receivers need `Self: Send`, never `Self: Sync`
+#[bitte(Send, Sync)]
+trait Accumulator {
+    async fn bump(&mut self) -> u32;
+    async fn finish(self) -> u32;
+}
+
+// `Send` but not `Sync`, so only usable through exclusive receivers
+struct CellCounter {
+    count: Cell<u32>,
+}
+
+#[bitte(Send, Sync)]
+impl Accumulator for CellCounter {
+    async fn bump(&mut self) -> u32 {
+        self.count.set(self.count.get() + 1);
+        tokio::task::yield_now().await;
+        self.count.get()
+    }
+
+    async fn finish(self) -> u32 {
+        tokio::task::yield_now().await;
+        self.count.into_inner()
+    }
+}
+
+// Generic code can spawn exclusive-receiver futures knowing only `T: Send`
+async fn bump_in_background<T: Accumulator + Send + 'static>(mut counter: T) -> u32 {
+    tokio::spawn(async move {
+        counter.bump().await;
+        counter.finish().await
+    })
+    .await
+    .unwrap()
+}
+
+// Generic code can spawn shared-receiver futures knowing `T: Send + Sync`
+async fn get_in_background<T: Counter + Send + Sync + 'static>(counter: Arc<T>) -> u32 {
+    tokio::spawn(counter.get_shared()).await.unwrap()
+}
+
+struct AtomicCounter {
+    count: std::sync::atomic::AtomicU32,
+}
+
+#[bitte(Send, Sync)]
+impl Counter for AtomicCounter {
+    async fn get(&self) -> u32 {
+        self.count.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    async fn get_shared(self: Arc<Self>) -> u32 {
+        self.get().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_exclusive_receivers_only_need_send() {
+        let counter = CellCounter {
+            count: Cell::new(1),
+        };
+
+        assert_eq!(bump_in_background(counter).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_shared_receivers_need_sync() {
+        let counter = Arc::new(AtomicCounter {
+            count: std::sync::atomic::AtomicU32::new(5),
+        });
+
+        assert_eq!(counter.get().await, 5);
+        assert_eq!(get_in_background(counter).await, 5);
+    }
+}
+
+// Smart-pointer and pinned receivers, in a shape connection pools use
+#[bitte(Send, Sync)]
+trait Connection {
+    async fn poll_ready(self: std::pin::Pin<&mut Self>) -> bool;
+    async fn checkout(self: &Arc<Self>) -> u32;
+    async fn close(self: Box<Self>) -> u32;
+    async fn close_pinned(self: std::pin::Pin<Box<Self>>) -> u32;
+    async fn share(self: std::sync::Arc<Self>) -> u32;
+    async fn share_pinned(self: std::pin::Pin<Arc<Self>>) -> u32;
+}
+
+#[bitte(?Send)]
+trait LocalConnection {
+    async fn checkout_local(self: std::rc::Rc<Self>) -> u32;
+}
+
+struct Pooled {
+    id: u32,
+}
+
+#[bitte(Send, Sync)]
+impl Connection for Pooled {
+    async fn poll_ready(self: std::pin::Pin<&mut Self>) -> bool {
+        tokio::task::yield_now().await;
+        self.id > 0
+    }
+
+    async fn checkout(self: &Arc<Self>) -> u32 {
+        self.id
+    }
+
+    async fn close(self: Box<Self>) -> u32 {
+        self.id
+    }
+
+    async fn close_pinned(self: std::pin::Pin<Box<Self>>) -> u32 {
+        self.id
+    }
+
+    async fn share(self: std::sync::Arc<Self>) -> u32 {
+        self.id
+    }
+
+    async fn share_pinned(self: std::pin::Pin<Arc<Self>>) -> u32 {
+        self.id
+    }
+}
+
+#[bitte(?Send)]
+impl LocalConnection for Pooled {
+    async fn checkout_local(self: std::rc::Rc<Self>) -> u32 {
+        self.id
+    }
+}
+
+// Pinned exclusive receivers only need `T: Send` to be spawned
+async fn ready_in_background<T: Connection + Send + 'static>(conn: T) -> bool {
+    tokio::spawn(async move {
+        let mut conn = Box::pin(conn);
+        conn.as_mut().poll_ready().await
+    })
+    .await
+    .unwrap()
+}
+
+// Borrowed `Arc`s need the same bounds as `&self`
+async fn checkout_in_background<T: Connection + Send + Sync + 'static>(conn: Arc<T>) -> u32 {
+    tokio::spawn(async move { conn.checkout().await })
+        .await
+        .unwrap()
+}
+
+#[cfg(test)]
+mod pointer_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pinned_and_smart_pointer_receivers() {
+        assert!(ready_in_background(Pooled { id: 1 }).await);
+        assert_eq!(checkout_in_background(Arc::new(Pooled { id: 2 })).await, 2);
+
+        assert_eq!(Box::new(Pooled { id: 3 }).close().await, 3);
+        assert_eq!(Box::pin(Pooled { id: 4 }).close_pinned().await, 4);
+        assert_eq!(Arc::new(Pooled { id: 5 }).share().await, 5);
+        assert_eq!(Arc::pin(Pooled { id: 6 }).share_pinned().await, 6);
+        assert_eq!(std::rc::Rc::new(Pooled { id: 7 }).checkout_local().await, 7);
+    }
+}
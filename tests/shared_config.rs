@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::Cell;
+use std::rc::Rc;
+
+// Declared `?Sync`, which an impl using the `threads` default wouldn’t match
+#[bitte(?Send, ?Sync)]
+pub trait Counter {
+    async fn next(&self) -> u64;
+
+    #[bitte(Send)]
+    async fn start() -> u64;
+}
+
+struct LocalCounter {
+    count: Rc<Cell<u64>>,
+}
+
+// Desugared every method the way the trait was, including `start`
+#[bitte]
+impl Counter for LocalCounter {
+    async fn next(&self) -> u64 {
+        let count = Rc::clone(&self.count);
+        tokio::task::yield_now().await;
+        count.set(count.get() + 1);
+        count.get()
+    }
+
+    async fn start() -> u64 {
+        1
+    }
+}
+
+mod storage {
+    use bitte::bitte;
+
+    #[bitte(Send, Sync, named)]
+    pub trait Source<T> {
+        async fn get(&self, key: &str) -> Option<T>;
+    }
+}
+
+struct Fixed(u32);
+
+// The trait’s path finds its configuration, from any module
+#[bitte]
+impl storage::Source<u32> for Fixed {
+    async fn get(&self, _key: &str) -> Option<u32> {
+        tokio::task::yield_now().await;
+        Some(self.0)
+    }
+}
+
+// A trait declared without `#[bitte]` has no configuration to share
+pub trait Handwritten {
+    fn value(&self) -> impl Future<Output = u32> + Send;
+}
+
+#[bitte(Send, ?trait)]
+impl Handwritten for Fixed {
+    async fn value(&self) -> u32 {
+        tokio::task::yield_now().await;
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage::Source;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_impl_matches_local_trait() {
+        let start = LocalCounter::start();
+        assert_send(&start);
+        let counter = LocalCounter {
+            count: Rc::new(Cell::new(start.await)),
+        };
+        assert_eq!(counter.next().await, 2);
+        assert_eq!(counter.next().await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_impl_matches_named_trait() {
+        let source = Fixed(7);
+        let future: <Fixed as Source<u32>>::GetFuture<'_> = source.get("key");
+        assert_send(&future);
+        assert_eq!(future.await, Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_impl_of_plain_trait() {
+        let future = Fixed(7).value();
+        assert_send(&future);
+        assert_eq!(future.await, 7);
+    }
+}
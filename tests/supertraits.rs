@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// The receivers need `Self: Send + Sync`, which the trait now requires
+#[bitte(Send, Sync, supertraits)]
+pub trait Service {
+    async fn call(&self, request: u32) -> u32;
+    async fn reset(&mut self);
+}
+
+// Only what the methods need is added, here just `Send`
+#[bitte(Send, Sync, supertraits)]
+pub trait Worker {
+    async fn run(self) -> u32;
+}
+
+struct Adder {
+    total: AtomicU32,
+}
+
+#[bitte(Send, Sync, supertraits)]
+impl Service for Adder {
+    async fn call(&self, request: u32) -> u32 {
+        tokio::task::yield_now().await;
+        self.total.fetch_add(request, Ordering::SeqCst) + request
+    }
+
+    async fn reset(&mut self) {
+        *self.total.get_mut() = 0;
+    }
+}
+
+// `Send` but not `Sync`
+struct CellWorker {
+    value: Cell<u32>,
+}
+
+// Impls may also keep the `where` clauses, which the supertraits imply
+#[bitte(Send, Sync)]
+impl Worker for CellWorker {
+    async fn run(self) -> u32 {
+        tokio::task::yield_now().await;
+        self.value.get()
+    }
+}
+
+fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+// Generic code gets the auto traits from the trait bound alone
+fn check_service<S: Service>() {
+    assert_send_sync::<S>();
+}
+
+async fn call_in_background<S: Service + 'static>(service: std::sync::Arc<S>) -> u32 {
+    tokio::spawn(async move { service.call(2).await })
+        .await
+        .unwrap()
+}
+
+async fn run_in_background<W: Worker + 'static>(worker: W) -> u32 {
+    tokio::spawn(worker.run()).await.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_supertraits_bound_the_trait() {
+        check_service::<Adder>();
+
+        let service = std::sync::Arc::new(Adder {
+            total: AtomicU32::new(1),
+        });
+        assert_eq!(call_in_background(service).await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_supertraits_are_minimal() {
+        let worker = CellWorker {
+            value: Cell::new(4),
+        };
+        assert_eq!(run_in_background(worker).await, 4);
+    }
+}
@@ -16,6 +16,13 @@ mod threads_enabled {
         async fn method(&self) -> u32;
     }
 
+    // The default can't make an `Rc<Self>` future `Send`, so it's skipped
+    #[bitte]
+    trait AsyncTraitThreadsRc {
+        async fn shared(&self) -> u32;
+        async fn counted(self: std::rc::Rc<Self>) -> u32;
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
@@ -43,6 +50,24 @@ mod threads_enabled {
             }
         }
 
+        impl AsyncTraitThreadsRc for TestImpl {
+            fn shared(&self) -> impl std::future::Future<Output = u32> + Send {
+                async move { 1 }
+            }
+
+            fn counted(self: std::rc::Rc<Self>) -> impl std::future::Future<Output = u32> {
+                let local = self.clone();
+                async move { local.shared().await + 1 }
+            }
+        }
+
+        #[tokio::test]
+        async fn test_rc_receiver_skips_default_send() {
+            let test_impl = std::rc::Rc::new(TestImpl);
+            assert_send(test_impl.shared());
+            assert_eq!(test_impl.counted().await, 2);
+        }
+
         #[test]
         fn test_threads_bounds() {
             let test_impl = TestImpl;
@@ -96,3 +121,75 @@ mod threads_disabled {
         }
     }
 }
+
+#[cfg(feature = "tokio")]
+mod spawn_helpers {
+    use bitte::bitte;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // Each `&self` method gets `spawn_*`, which spawns its owned future
+    #[bitte(Send, Sync, spawn)]
+    pub trait Counter {
+        async fn add(&self, amount: u64) -> u64;
+        async fn reset(&self);
+    }
+
+    // A `?Send` method's future can only be spawned on the current thread
+    #[bitte(?Send, ?Sync, spawn)]
+    pub trait LocalCounter {
+        async fn bump(&self, amount: u64) -> u64;
+    }
+
+    #[derive(Default)]
+    struct Total(AtomicU64);
+
+    #[bitte]
+    impl Counter for Total {
+        async fn add(&self, amount: u64) -> u64 {
+            tokio::task::yield_now().await;
+            self.0.fetch_add(amount, Ordering::SeqCst) + amount
+        }
+
+        async fn reset(&self) {
+            self.0.store(0, Ordering::SeqCst);
+        }
+    }
+
+    impl LocalCounter for Total {
+        async fn bump(&self, amount: u64) -> u64 {
+            self.add(amount).await
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_spawn_methods() {
+            let total = Arc::new(Total::default());
+            let tasks: Vec<_> = (1..=4)
+                .map(|amount| Arc::clone(&total).spawn_add(amount))
+                .collect();
+            for task in tasks {
+                task.await.unwrap();
+            }
+            assert_eq!(total.0.load(Ordering::SeqCst), 10);
+
+            Arc::clone(&total).spawn_reset().await.unwrap();
+            assert_eq!(total.0.load(Ordering::SeqCst), 0);
+        }
+
+        #[tokio::test]
+        async fn test_spawn_local_methods() {
+            let total = Arc::new(Total::default());
+            let local = tokio::task::LocalSet::new();
+            let value = local
+                .run_until(async { total.spawn_local_bump(2).await })
+                .await
+                .unwrap();
+            assert_eq!(value, 2);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use bitte::bitte;
+use std::rc::Rc;
+
+#[bitte(assert(Send))]
+async fn count() -> u64 {
+    let count = Rc::new(1);
+    std::future::ready(()).await;
+    *count
+}
+
+fn main() {}
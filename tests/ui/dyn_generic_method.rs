@@ -0,0 +1,8 @@
+use bitte::bitte;
+
+#[bitte(dyn)]
+trait Encoder {
+    async fn encode<T: AsRef<[u8]>>(&self, value: T) -> Vec<u8>;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use bitte::bitte;
+
+#[bitte(?Send, named, helper(EncoderSend))]
+trait Encoder {
+    async fn encode<T: AsRef<[u8]>>(&self, value: T) -> Vec<u8>;
+}
+
+fn main() {}
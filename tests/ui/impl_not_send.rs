@@ -0,0 +1,20 @@
+use bitte::bitte;
+use std::rc::Rc;
+
+#[bitte(Send, Sync)]
+trait Counter {
+    async fn next(&self) -> u64;
+}
+
+struct LocalCounter;
+
+#[bitte(Send, Sync)]
+impl Counter for LocalCounter {
+    async fn next(&self) -> u64 {
+        let count = Rc::new(1);
+        std::future::ready(()).await;
+        *count
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use bitte::bitte;
+
+#[bitte(Send)]
+trait Named {
+    async fn name(&self) -> String;
+}
+
+struct User;
+
+impl Named for User {
+    #[bitte(Send, inner)]
+    async fn name(&self) -> String {
+        "user".to_string()
+    }
+}
+
+fn main() {}
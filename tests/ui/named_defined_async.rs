@@ -0,0 +1,22 @@
+use bitte::bitte;
+
+#[bitte(Send, Sync, named)]
+trait Counter {
+    async fn next(&self) -> u64;
+}
+
+struct Zero;
+
+#[bitte(Send, Sync, named)]
+impl Counter for Zero {
+    type NextFuture<'future>
+        = std::future::Ready<u64>
+    where
+        Self: 'future;
+
+    async fn next(&self) -> u64 {
+        0
+    }
+}
+
+fn main() {}
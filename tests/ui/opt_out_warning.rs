@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+
+use bitte::bitte;
+
+// `?Sync` can't be honored without dropping what a `Send` future needs
+#[bitte(Send, ?Sync)]
+trait Contradictory {
+    async fn method(&self) -> u32;
+}
+
+fn main() {}
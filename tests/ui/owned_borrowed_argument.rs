@@ -0,0 +1,16 @@
+use bitte::bitte;
+use std::sync::Arc;
+
+#[bitte(Send, Sync, owned)]
+trait Store {
+    async fn get(&self, key: String) -> Option<u64>;
+    // Borrowing `key`, its future can't be `'static`
+    async fn contains(&self, key: &str) -> bool;
+}
+
+async fn check<S: Store + Send + Sync + 'static>(store: Arc<S>) {
+    Arc::clone(&store).get_owned("a".to_string()).await;
+    store.contains_owned("a").await;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use bitte::bitte;
+
+#[bitte(Send)]
+trait LocalOnly {
+    async fn method(self: std::rc::Rc<Self>) -> u32;
+}
+
+fn main() {}
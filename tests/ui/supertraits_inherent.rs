@@ -0,0 +1,12 @@
+use bitte::bitte;
+
+struct Client;
+
+#[bitte(Send, Sync, supertraits)]
+impl Client {
+    async fn get(&self) -> u32 {
+        1
+    }
+}
+
+fn main() {}
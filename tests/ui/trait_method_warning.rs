@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+
+use bitte::bitte;
+
+trait Contradictory {
+    // A bodyless method still reports its warnings
+    #[bitte(Send, ?Sync)]
+    async fn method(&self) -> u32;
+}
+
+fn main() {}
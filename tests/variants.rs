@@ -0,0 +1,125 @@
+#![allow(dead_code)]
+
+use bitte::bitte;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::sync::Mutex;
+
+// Emits `Store`, without bounds, and `SendStore`, whose futures are `Send`
+#[bitte(variant(SendStore: Send + Sync))]
+pub trait Store {
+    type Key;
+    const NAME: &'static str;
+
+    async fn get(&self, key: Self::Key) -> Option<String>;
+    async fn put(&mut self, key: Self::Key, value: String);
+    async fn put_display<T: Display>(&mut self, key: Self::Key, value: &T);
+    fn count(&self) -> usize;
+
+    async fn describe(&self) -> String {
+        format!("{} store with {} entries", Self::NAME, self.count())
+    }
+}
+
+struct SharedStore {
+    entries: Mutex<HashMap<u32, String>>,
+}
+
+#[bitte(Send, Sync)]
+impl SendStore for SharedStore {
+    type Key = u32;
+    const NAME: &'static str = "shared";
+
+    async fn get(&self, key: u32) -> Option<String> {
+        tokio::task::yield_now().await;
+        self.entries.lock().unwrap().get(&key).cloned()
+    }
+
+    async fn put(&mut self, key: u32, value: String) {
+        tokio::task::yield_now().await;
+        self.entries.get_mut().unwrap().insert(key, value);
+    }
+
+    async fn put_display<T: Display>(&mut self, key: u32, value: &T) {
+        tokio::task::yield_now().await;
+        self.entries
+            .get_mut()
+            .unwrap()
+            .insert(key, value.to_string());
+    }
+
+    fn count(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+}
+
+// Implements only the local trait, holding an `Rc` across awaits
+struct LocalStore {
+    entries: Rc<RefCell<HashMap<u32, String>>>,
+}
+
+#[bitte(?Send, ?Sync)]
+impl Store for LocalStore {
+    type Key = u32;
+    const NAME: &'static str = "local";
+
+    async fn get(&self, key: u32) -> Option<String> {
+        let entries = self.entries.clone();
+        tokio::task::yield_now().await;
+        entries.borrow().get(&key).cloned()
+    }
+
+    async fn put(&mut self, key: u32, value: String) {
+        let entries = self.entries.clone();
+        tokio::task::yield_now().await;
+        entries.borrow_mut().insert(key, value);
+    }
+
+    async fn put_display<T: Display>(&mut self, key: u32, value: &T) {
+        self.put(key, value.to_string()).await;
+    }
+
+    fn count(&self) -> usize {
+        self.entries.borrow().len()
+    }
+}
+
+// Generic code written against the local trait accepts both
+async fn fill<S: Store<Key = u32>>(store: &mut S) -> String {
+    store.put(1, "one".to_string()).await;
+    store.put_display(2, &2).await;
+    assert_eq!(store.get(1).await.as_deref(), Some("one"));
+    store.describe().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>(_: &T) {}
+
+    #[tokio::test]
+    async fn test_send_variant() {
+        let mut store = SharedStore {
+            entries: Mutex::new(HashMap::new()),
+        };
+
+        let future = SendStore::put(&mut store, 3, "three".to_string());
+        assert_send(&future);
+        future.await;
+
+        assert_eq!(fill(&mut store).await, "shared store with 3 entries");
+        assert_eq!(<SharedStore as Store>::NAME, "shared");
+    }
+
+    #[tokio::test]
+    async fn test_local_trait() {
+        let mut store = LocalStore {
+            entries: Rc::default(),
+        };
+
+        assert_eq!(fill(&mut store).await, "local store with 2 entries");
+    }
+}